@@ -0,0 +1,135 @@
+//! Compares two versions of a command line interface and reports breaking changes.
+//!
+//! Exits with code `1` if there are any breaking changes, this makes it usable in CI.
+//!
+//! - `compat` compares `OLD` with `NEW`,
+//! - `compat --export` prints the schema of `NEW`, i.e. to save it with a release,
+//! - `compat <schema>` compares a saved schema with `NEW`.
+
+use std::{env, fs, process};
+
+use vvvv::{
+    compat::{self, Schema},
+    help::{Description, Kind, Opt, Pos, Required},
+};
+
+const OLD: Description<'static> = Description::DEFAULT
+    .name("cp")
+    .descr("Copies files")
+    .usage("cp [-v] [-n] [--backup <suffix>] <src> <dst>")
+    .positionals(&[
+        Pos {
+            name: "src",
+            descr: "source file",
+            required: Required::Required,
+            ..Pos::DEFAULT
+        },
+        Pos {
+            name: "dst",
+            descr: "destination file",
+            required: Required::Required,
+            ..Pos::DEFAULT
+        },
+    ])
+    .options(&[
+        Opt {
+            short: Some('v'),
+            long: Some("verbose"),
+            kind: Kind::Flag,
+            descr: "explain what is being done",
+            required: Required::Optional,
+            ..Opt::DEFAULT
+        },
+        Opt {
+            short: Some('n'),
+            long: Some("no-clobber"),
+            kind: Kind::Flag,
+            descr: "do not overwrite an existing file",
+            required: Required::Optional,
+            ..Opt::DEFAULT
+        },
+        Opt {
+            short: None,
+            long: Some("backup"),
+            kind: Kind::Value {
+                name: Some("suffix"),
+                default: Some("~"),
+            },
+            descr: "make a backup of each existing destination file",
+            required: Required::Optional,
+            ..Opt::DEFAULT
+        },
+    ]);
+
+const NEW: Description<'static> = Description::DEFAULT
+    .name("cp")
+    .descr("Copies files")
+    .usage("cp [-v...] [-n] [--backup] <src> <dst>")
+    .positionals(&[
+        Pos {
+            name: "src",
+            descr: "source file",
+            required: Required::Required,
+            ..Pos::DEFAULT
+        },
+        Pos {
+            name: "dst",
+            descr: "destination file",
+            required: Required::Required,
+            ..Pos::DEFAULT
+        },
+    ])
+    .options(&[
+        Opt {
+            short: Some('v'),
+            long: Some("verbose"),
+            kind: Kind::Count,
+            descr: "explain what is being done, repeat for more details",
+            required: Required::Optional,
+            ..Opt::DEFAULT
+        },
+        Opt {
+            short: Some('n'),
+            long: Some("dry-run"),
+            kind: Kind::Flag,
+            descr: "do not copy anything",
+            required: Required::Optional,
+            ..Opt::DEFAULT
+        },
+        Opt {
+            short: None,
+            long: Some("backup"),
+            kind: Kind::Flag,
+            descr: "make a backup of each existing destination file",
+            required: Required::Optional,
+            ..Opt::DEFAULT
+        },
+    ]);
+
+fn main() {
+    match env::args().nth(1).as_deref() {
+        None => check(&compat::diff(&OLD, &NEW)),
+        Some("--export") => print!("{}", Schema::new(&NEW)),
+        Some(path) => {
+            let text = fs::read_to_string(path).unwrap_or_else(|err| {
+                eprintln!("Can't read `{}`: {}", path, err);
+                process::exit(2)
+            });
+            let old = Schema::parse(&text).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                process::exit(2)
+            });
+
+            check(&compat::diff_schemas(&old, &Schema::new(&NEW)));
+        }
+    }
+}
+
+/// Prints `report`, exits with code `1` if there are breaking changes.
+fn check(report: &compat::Report<'_>) {
+    print!("{}", report);
+
+    if report.is_breaking() {
+        process::exit(1);
+    }
+}
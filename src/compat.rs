@@ -0,0 +1,1310 @@
+//! Compatibility checks between two versions of a command line interface.
+//!
+//! [`diff`] compares an old and a new [`Description`] and reports changes which may break
+//! existing invocations (e.g. removed options) separately from purely additive ones.
+//!
+//! Only [`Description::Typed`] can be compared, `Raw` and `None` descriptions never report any
+//! changes.
+
+use std::{
+    error,
+    fmt::{self, Display},
+    ops::Range,
+};
+
+use crate::help::{self, Alias, Description, Kind, Opt, Pos, Required};
+
+/// Result of comparing two [`Description`]s, see [`diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report<'a> {
+    /// Changes which may break existing invocations.
+    pub breaking: Vec<Change<'a>>,
+    /// Changes which keep all existing invocations working.
+    pub additive: Vec<Change<'a>>,
+}
+
+impl Report<'_> {
+    /// Returns `true` if there are any breaking changes.
+    pub fn is_breaking(&self) -> bool {
+        !self.breaking.is_empty()
+    }
+
+    /// Returns `true` if there are no changes at all.
+    pub fn is_empty(&self) -> bool {
+        self.breaking.is_empty() && self.additive.is_empty()
+    }
+}
+
+impl Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (title, changes) in [("Breaking", &self.breaking), ("Additive", &self.additive)] {
+            if changes.is_empty() {
+                continue;
+            }
+
+            writeln!(f, "{} changes:", title)?;
+            for change in changes {
+                writeln!(f, "  {}", change)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A single difference between two [`Description`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Change<'a> {
+    /// Option was removed.
+    OptionRemoved(Opt<'a>),
+    /// Option was added.
+    ///
+    /// This is breaking if the option is required.
+    OptionAdded(Opt<'a>),
+    /// Short name of an option was removed or changed (and is not kept as an alias).
+    ShortRemoved { old: Opt<'a>, new: Opt<'a> },
+    /// Short name was added to an option or changed while keeping the old one as an alias.
+    ShortAdded { old: Opt<'a>, new: Opt<'a> },
+    /// Long name of an option was removed or changed (and is not kept as an alias).
+    LongRemoved { old: Opt<'a>, new: Opt<'a> },
+    /// Long name was added to an option or changed while keeping the old one as an alias.
+    LongAdded { old: Opt<'a>, new: Opt<'a> },
+    /// Alias was removed from an option.
+    AliasRemoved {
+        name: help::Name<'a>,
+        old: Opt<'a>,
+        new: Opt<'a>,
+    },
+    /// Alias was added to an option.
+    AliasAdded {
+        name: help::Name<'a>,
+        old: Opt<'a>,
+        new: Opt<'a>,
+    },
+    /// Short name now belongs to a different option.
+    ShortReassigned {
+        short: char,
+        old: Opt<'a>,
+        new: Opt<'a>,
+    },
+    /// Kind of an option changed, i.e. a `Kind::Flag` turned into a `Kind::Value`.
+    ///
+    /// `Kind::Flag` -> `Kind::Count` is additive, all other changes are breaking.
+    KindChanged { old: Opt<'a>, new: Opt<'a> },
+    /// Option became required (or required in more cases).
+    BecameRequired { old: Opt<'a>, new: Opt<'a> },
+    /// Option is not required anymore (or required in less cases).
+    BecameOptional { old: Opt<'a>, new: Opt<'a> },
+    /// Positional argument at the given index was removed.
+    PositionalRemoved { index: usize, old: Pos<'a> },
+    /// Positional argument at the given index was added.
+    ///
+    /// This is breaking if the positional argument is required.
+    PositionalAdded { index: usize, new: Pos<'a> },
+    /// Positional argument at the given index became required (or required in more cases).
+    PositionalBecameRequired {
+        index: usize,
+        old: Pos<'a>,
+        new: Pos<'a>,
+    },
+    /// Positional argument at the given index is not required anymore (or required in less
+    /// cases).
+    PositionalBecameOptional {
+        index: usize,
+        old: Pos<'a>,
+        new: Pos<'a>,
+    },
+}
+
+impl Display for Change<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::OptionRemoved(opt) => write!(f, "option `{}` was removed", Name(opt)),
+            Change::OptionAdded(opt) => write!(f, "option `{}` was added", Name(opt)),
+            Change::ShortRemoved { old, new } => match (old.short, new.short) {
+                (Some(o), Some(n)) => write!(
+                    f,
+                    "short name of `{}` was changed from `-{}` to `-{}`",
+                    Name(new),
+                    o,
+                    n
+                ),
+                (o, _) => write!(
+                    f,
+                    "short name `-{}` of `{}` was removed",
+                    o.unwrap_or('?'),
+                    Name(new)
+                ),
+            },
+            Change::ShortAdded { new, .. } => write!(
+                f,
+                "short name `-{}` was added to `{}`",
+                new.short.unwrap_or('?'),
+                Name(new)
+            ),
+            Change::LongRemoved { old, new } => match (old.long, new.long) {
+                (Some(o), Some(n)) => {
+                    write!(f, "long name `--{}` was changed to `--{}`", o, n)
+                }
+                (o, _) => write!(
+                    f,
+                    "long name `--{}` of `{}` was removed",
+                    o.unwrap_or("?"),
+                    Name(new)
+                ),
+            },
+            Change::LongAdded { old, new } => write!(
+                f,
+                "long name `--{}` was added to `{}`",
+                new.long.unwrap_or(""),
+                Name(old)
+            ),
+            Change::AliasRemoved { name, new, .. } => {
+                write!(f, "alias `{}` of `{}` was removed", name, Name(new))
+            }
+            Change::AliasAdded { name, new, .. } => {
+                write!(f, "alias `{}` was added to `{}`", name, Name(new))
+            }
+            Change::ShortReassigned { short, old, new } => write!(
+                f,
+                "short name `-{}` was moved from `{}` to `{}`",
+                short,
+                Name(old),
+                Name(new)
+            ),
+            Change::KindChanged { old, new } => write!(
+                f,
+                "option `{}` changed from {} to {}",
+                Name(new),
+                kind_name(&old.kind),
+                kind_name(&new.kind)
+            ),
+            Change::BecameRequired { new, .. } => {
+                write!(f, "option `{}` became required", Name(new))
+            }
+            Change::BecameOptional { new, .. } => {
+                write!(f, "option `{}` became optional", Name(new))
+            }
+            Change::PositionalRemoved { index, old } => write!(
+                f,
+                "positional argument #{} `{}` was removed",
+                index + 1,
+                old.name
+            ),
+            Change::PositionalAdded { index, new } => write!(
+                f,
+                "positional argument #{} `{}` was added",
+                index + 1,
+                new.name
+            ),
+            Change::PositionalBecameRequired { index, new, .. } => write!(
+                f,
+                "positional argument #{} `{}` became required",
+                index + 1,
+                new.name
+            ),
+            Change::PositionalBecameOptional { index, new, .. } => write!(
+                f,
+                "positional argument #{} `{}` became optional",
+                index + 1,
+                new.name
+            ),
+        }
+    }
+}
+
+/// Compares `old` and `new` descriptions.
+///
+/// Options are matched if any long name of the old option (its long name or a long alias) is
+/// still a name of the new option, i.e. `--colour` renamed to `--color` with a `colour` alias.
+/// Options without a matching long name are matched by their short names in the same way.
+/// Positional arguments are matched by their index.
+///
+/// ## Examples
+///
+/// ```
+/// use vvvv::{
+///     compat,
+///     help::{Description, Kind, Opt, Required},
+/// };
+///
+/// const VERBOSE: Opt = Opt {
+///     short: Some('v'),
+///     long: Some("verbose"),
+///     kind: Kind::Flag,
+///     descr: "be verbose",
+///     required: Required::Optional,
+///     ..Opt::DEFAULT
+/// };
+///
+/// let old = Description::DEFAULT.options(&[VERBOSE]);
+/// let new = Description::DEFAULT.options(&[Opt { kind: Kind::Count, ..VERBOSE }]);
+///
+/// let report = compat::diff(&old, &new);
+/// assert!(!report.is_breaking());
+/// assert!(compat::diff(&new, &old).is_breaking());
+/// ```
+pub fn diff<'a>(old: &Description<'a>, new: &Description<'a>) -> Report<'a> {
+    match (old, new) {
+        (
+            Description::Typed {
+                positionals: old_pos,
+                options: old_opts,
+                ..
+            },
+            Description::Typed {
+                positionals: new_pos,
+                options: new_opts,
+                ..
+            },
+        ) => diff_parts(old_pos, old_opts, new_pos, new_opts),
+        _ => Report {
+            breaking: Vec::new(),
+            additive: Vec::new(),
+        },
+    }
+}
+
+/// Compares two [exported](Schema) interfaces, i.e. a schema saved by a previous release with
+/// the schema of the current [`Description`].
+///
+/// ## Examples
+///
+/// ```
+/// use vvvv::{
+///     compat::{self, Schema},
+///     help::{Description, Kind, Opt},
+/// };
+///
+/// let old = Schema::parse("vvvv-schema 1\noption -v --verbose flag optional\n").unwrap();
+/// let new = Description::DEFAULT.options(&[Opt {
+///     short: Some('v'),
+///     long: Some("verbose"),
+///     kind: Kind::Count,
+///     ..Opt::DEFAULT
+/// }]);
+///
+/// let new = Schema::new(&new);
+/// let report = compat::diff_schemas(&old, &new);
+/// assert!(!report.is_empty());
+/// assert!(!report.is_breaking());
+/// ```
+pub fn diff_schemas<'s>(old: &'s Schema<'_>, new: &'s Schema<'_>) -> Report<'s> {
+    diff_parts(
+        &old.positionals,
+        &old.options(),
+        &new.positionals,
+        &new.options(),
+    )
+}
+
+fn diff_parts<'a>(
+    old_pos: &[Pos<'a>],
+    old_opts: &[Opt<'a>],
+    new_pos: &[Pos<'a>],
+    new_opts: &[Opt<'a>],
+) -> Report<'a> {
+    let mut report = Report {
+        breaking: Vec::new(),
+        additive: Vec::new(),
+    };
+
+    let mut matched = vec![false; new_opts.len()];
+    // options are paired if any name of the old option (including aliases) is still a name of
+    // the new one, long names take precedence over short ones
+    let same_long = |old: &Opt<'a>, new: &Opt<'a>| {
+        old.long_names()
+            .any(|long| new.has_name(help::Name::Long(long)))
+    };
+    let same_short = |old: &Opt<'a>, new: &Opt<'a>| {
+        short_names(old).any(|short| new.has_name(help::Name::Short(short)))
+    };
+
+    for old in old_opts.iter() {
+        // options which lost their long names are still matched by their short names, unless
+        // the new option is the counterpart of another option
+        let found = new_opts
+            .iter()
+            .position(|new| same_long(old, new))
+            .or_else(|| {
+                new_opts.iter().position(|new| {
+                    same_short(old, new) && !old_opts.iter().any(|other| same_long(other, new))
+                })
+            });
+
+        match found {
+            None => report.breaking.push(Change::OptionRemoved(*old)),
+            Some(i) => {
+                matched[i] = true;
+                diff_opt(&mut report, old, &new_opts[i]);
+            }
+        }
+
+        let counterpart = found.map(|i| &new_opts[i]);
+        let owner = new_opts
+            .iter()
+            .find(|new| matches!(old.short, Some(short) if new.has_name(help::Name::Short(short))));
+        if let (Some(short), Some(owner)) = (old.short, owner) {
+            if Some(owner) != counterpart {
+                report.breaking.push(Change::ShortReassigned {
+                    short,
+                    old: *old,
+                    new: *owner,
+                });
+            }
+        }
+    }
+
+    for (new, _) in new_opts.iter().zip(matched).filter(|(_, m)| !m) {
+        let change = Change::OptionAdded(*new);
+        match new.required {
+            Required::Required => report.breaking.push(change),
+            _ => report.additive.push(change),
+        }
+    }
+
+    for (index, (&old, &new)) in old_pos.iter().zip(new_pos).enumerate() {
+        match stricter(&old.required, &new.required) {
+            Some(true) => {
+                report
+                    .breaking
+                    .push(Change::PositionalBecameRequired { index, old, new })
+            }
+            Some(false) => {
+                report
+                    .additive
+                    .push(Change::PositionalBecameOptional { index, old, new })
+            }
+            None => {}
+        }
+    }
+
+    for (index, old) in old_pos.iter().enumerate().skip(new_pos.len()) {
+        report
+            .breaking
+            .push(Change::PositionalRemoved { index, old: *old });
+    }
+
+    for (index, new) in new_pos.iter().enumerate().skip(old_pos.len()) {
+        let change = Change::PositionalAdded { index, new: *new };
+        match new.required {
+            Required::Required => report.breaking.push(change),
+            _ => report.additive.push(change),
+        }
+    }
+
+    report
+}
+
+fn diff_opt<'a>(report: &mut Report<'a>, old: &Opt<'a>, new: &Opt<'a>) {
+    let (old, new) = (*old, *new);
+
+    // names which are still accepted, i.e. as an alias, are not reported
+    match (old.short, new.short) {
+        (Some(o), _) if !new.has_name(help::Name::Short(o)) => {
+            report.breaking.push(Change::ShortRemoved { old, new })
+        }
+        (_, Some(n)) if !old.has_name(help::Name::Short(n)) => {
+            report.additive.push(Change::ShortAdded { old, new })
+        }
+        _ => {}
+    }
+
+    match (old.long, new.long) {
+        (Some(o), _) if !new.has_name(help::Name::Long(o)) => {
+            report.breaking.push(Change::LongRemoved { old, new })
+        }
+        (_, Some(n)) if !old.has_name(help::Name::Long(n)) => {
+            report.additive.push(Change::LongAdded { old, new })
+        }
+        _ => {}
+    }
+
+    for alias in old.aliases.iter().filter(|alias| !new.has_name(alias.name)) {
+        report.breaking.push(Change::AliasRemoved {
+            name: alias.name,
+            old,
+            new,
+        });
+    }
+
+    for alias in new.aliases.iter().filter(|alias| !old.has_name(alias.name)) {
+        report.additive.push(Change::AliasAdded {
+            name: alias.name,
+            old,
+            new,
+        });
+    }
+
+    match (&old.kind, &new.kind) {
+        (Kind::Flag, Kind::Flag)
+        | (Kind::Count, Kind::Count)
+        | (Kind::Value { .. }, Kind::Value { .. }) => {}
+        (Kind::Flag, Kind::Count) => report.additive.push(Change::KindChanged { old, new }),
+        _ => report.breaking.push(Change::KindChanged { old, new }),
+    }
+
+    match stricter(&old.required, &new.required) {
+        Some(true) => report.breaking.push(Change::BecameRequired { old, new }),
+        Some(false) => report.additive.push(Change::BecameOptional { old, new }),
+        None => {}
+    }
+}
+
+/// First line of an exported [`Schema`].
+const HEADER: &str = "vvvv-schema 1";
+
+/// Values are exported without their name and default.
+const VALUE: Kind<'static> = Kind::Value {
+    name: None,
+    default: None,
+};
+
+/// Exported interface of a [`Description`], which can be saved to a file and compared with a
+/// later version by [`diff_schemas`].
+///
+/// Only what [`diff`] compares is exported: names, aliases, kinds and requirements of options and
+/// names and requirements of positional arguments. The text format is line based:
+///
+/// ```text
+/// vvvv-schema 1
+/// positional <name> <requirement>
+/// option [-s] [--long] [alias:-a] [alias:--name] <flag|count|value> <requirement>
+/// ```
+///
+/// where `<requirement>` is `required`, `optional` or `if <condition>`, the condition lasts until
+/// the end of the line. Empty lines and lines starting with `#` are ignored.
+///
+/// Words which are empty, contain whitespace or look like a quoted word are written as raw
+/// strings, like in Rust: `r"input file"` or `r#"say "hi""#`. Conditions with line breaks or
+/// surrounding whitespace are quoted the same way. Quoted words may span lines.
+///
+/// `Raw` and `None` descriptions export an empty schema.
+///
+/// ## Examples
+///
+/// ```
+/// use vvvv::{
+///     compat::Schema,
+///     help::{Description, Kind, Opt},
+/// };
+///
+/// let descr = Description::DEFAULT.options(&[Opt {
+///     short: Some('v'),
+///     long: Some("verbose"),
+///     kind: Kind::Count,
+///     ..Opt::DEFAULT
+/// }]);
+///
+/// let text = Schema::new(&descr).to_string();
+/// assert_eq!(text, "vvvv-schema 1\noption -v --verbose count optional\n");
+/// assert_eq!(Schema::parse(&text).unwrap(), Schema::new(&descr));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schema<'a> {
+    positionals: Vec<Pos<'a>>,
+    /// Options without aliases, aliases of `options[i]` are `aliases[ranges[i]]`.
+    options: Vec<Opt<'a>>,
+    ranges: Vec<Range<usize>>,
+    aliases: Vec<Alias<'a>>,
+}
+
+impl<'a> Schema<'a> {
+    /// Exports the interface of `descr`.
+    pub fn new(descr: &Description<'a>) -> Self {
+        let mut schema = Self {
+            positionals: Vec::new(),
+            options: Vec::new(),
+            ranges: Vec::new(),
+            aliases: Vec::new(),
+        };
+
+        if let Description::Typed {
+            positionals,
+            options,
+            ..
+        } = descr
+        {
+            for pos in positionals.iter() {
+                schema.positionals.push(Pos {
+                    name: pos.name,
+                    required: pos.required,
+                    ..Pos::DEFAULT
+                });
+            }
+
+            for opt in options.iter() {
+                let aliases = opt.aliases.iter().map(|alias| alias.name);
+                schema.push(
+                    Opt {
+                        short: opt.short,
+                        long: opt.long,
+                        kind: match opt.kind {
+                            Kind::Value { .. } => VALUE,
+                            kind => kind,
+                        },
+                        required: opt.required,
+                        ..Opt::DEFAULT
+                    },
+                    aliases,
+                );
+            }
+        }
+
+        schema
+    }
+
+    /// Parses a schema previously written by the `Display` implementation.
+    pub fn parse(text: &'a str) -> Result<Self, SchemaError> {
+        let mut schema = Self::new(&Description::None);
+        let mut words = Words {
+            rest: text,
+            line: 1,
+        };
+
+        let header = words.next_line();
+        let err = |line, reason| SchemaError { line, reason };
+        if header.is_none() || words.rest_of_line().map(str::trim_end) != Ok(HEADER) {
+            return Err(err(header.unwrap_or(1), "expected `vvvv-schema 1`"));
+        }
+
+        while let Some(line) = words.next_line() {
+            let err = |reason| err(line, reason);
+
+            match words.word().map_err(err)? {
+                Some("positional") => {
+                    let name = words.word().map_err(err)?;
+                    let name = name.ok_or_else(|| err("missing name"))?;
+                    let required = requirement(&mut words).map_err(err)?;
+                    schema.positionals.push(Pos {
+                        name,
+                        required,
+                        ..Pos::DEFAULT
+                    });
+                }
+                Some("option") => {
+                    let mut opt = Opt::DEFAULT;
+                    let mut aliases = Vec::new();
+
+                    loop {
+                        let word = words.word().map_err(err)?;
+                        let word = word.ok_or_else(|| err("missing kind"))?;
+                        opt.kind = match word {
+                            "flag" => Kind::Flag,
+                            "count" => Kind::Count,
+                            "value" => VALUE,
+                            _ => {
+                                match word.strip_prefix("alias:") {
+                                    Some(alias) => aliases.push(name(alias).map_err(err)?),
+                                    None => match name(word).map_err(err)? {
+                                        help::Name::Short(short) if opt.short.is_none() => {
+                                            opt.short = Some(short)
+                                        }
+                                        help::Name::Long(long) if opt.long.is_none() => {
+                                            opt.long = Some(long)
+                                        }
+                                        _ => return Err(err("duplicate name")),
+                                    },
+                                }
+                                continue;
+                            }
+                        };
+                        break;
+                    }
+
+                    opt.required = requirement(&mut words).map_err(err)?;
+                    schema.push(opt, aliases);
+                }
+                _ => return Err(err("expected `positional` or `option`")),
+            }
+        }
+
+        Ok(schema)
+    }
+
+    fn push(&mut self, opt: Opt<'a>, aliases: impl IntoIterator<Item = help::Name<'a>>) {
+        let start = self.aliases.len();
+        let aliases = aliases.into_iter().map(|name| Alias {
+            name,
+            visible: true,
+        });
+        self.aliases.extend(aliases);
+        self.ranges.push(start..self.aliases.len());
+        self.options.push(opt);
+    }
+
+    /// Returns options with their aliases.
+    fn options(&self) -> Vec<Opt<'_>> {
+        self.options
+            .iter()
+            .zip(&self.ranges)
+            .map(|(opt, range)| Opt {
+                aliases: &self.aliases[range.clone()],
+                ..*opt
+            })
+            .collect()
+    }
+}
+
+impl Display for Schema<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+
+        for pos in &self.positionals {
+            f.write_str("positional ")?;
+            write_word(f, pos.name)?;
+            f.write_str(" ")?;
+            write_requirement(f, &pos.required)?;
+        }
+
+        for opt in self.options() {
+            f.write_str("option ")?;
+            if let Some(short) = opt.short {
+                write_word(f, &format!("-{}", short))?;
+                f.write_str(" ")?;
+            }
+            if let Some(long) = opt.long {
+                write_word(f, &format!("--{}", long))?;
+                f.write_str(" ")?;
+            }
+            for alias in opt.aliases {
+                write_word(f, &format!("alias:{}", alias.name))?;
+                f.write_str(" ")?;
+            }
+            let kind = match opt.kind {
+                Kind::Value { .. } => "value",
+                Kind::Flag => "flag",
+                Kind::Count => "count",
+            };
+            write!(f, "{} ", kind)?;
+            write_requirement(f, &opt.required)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_requirement(f: &mut fmt::Formatter<'_>, req: &Required<'_>) -> fmt::Result {
+    match req {
+        Required::Required => writeln!(f, "required"),
+        Required::Optional => writeln!(f, "optional"),
+        Required::If(cond)
+            if !cond.is_empty()
+                && !cond.contains('\n')
+                && cond.trim() == *cond
+                && raw_start(cond).is_none() =>
+        {
+            writeln!(f, "if {}", cond)
+        }
+        Required::If(cond) => {
+            f.write_str("if ")?;
+            write_raw(f, cond)?;
+            writeln!(f)
+        }
+    }
+}
+
+/// Writes `word` so that it's read back as a single word.
+fn write_word(f: &mut fmt::Formatter<'_>, word: &str) -> fmt::Result {
+    if word.is_empty() || word.contains(char::is_whitespace) || raw_start(word).is_some() {
+        write_raw(f, word)
+    } else {
+        f.write_str(word)
+    }
+}
+
+/// Writes `text` as a raw string with as few `#` as possible.
+fn write_raw(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+    let mut hashes = String::new();
+    while text.contains(&format!("\"{}", hashes)) {
+        hashes.push('#');
+    }
+    write!(f, "r{0}\"{1}\"{0}", hashes, text)
+}
+
+/// Returns the number of `#` if `text` starts with a raw string, i.e. `r#"`.
+fn raw_start(text: &str) -> Option<usize> {
+    let hashes = text.strip_prefix('r')?;
+    let quote = hashes.trim_start_matches('#');
+    match quote.starts_with('"') {
+        true => Some(hashes.len() - quote.len()),
+        false => None,
+    }
+}
+
+/// Cursor over words of a schema.
+struct Words<'a> {
+    rest: &'a str,
+    /// Number of the line `rest` starts at.
+    line: usize,
+}
+
+impl<'a> Words<'a> {
+    /// Skips the rest of the current line and following empty and comment lines, returns the
+    /// number of the line with the next word.
+    fn next_line(&mut self) -> Option<usize> {
+        loop {
+            self.skip_blanks();
+            let end = match self.rest.find('\n') {
+                Some(_) if self.rest.starts_with('\n') => 0,
+                Some(end) if self.rest.starts_with('#') => end,
+                None if self.rest.starts_with('#') => self.rest.len(),
+                _ if self.rest.is_empty() => return None,
+                _ => return Some(self.line),
+            };
+            self.rest = &self.rest[end..];
+            if let Some(rest) = self.rest.strip_prefix('\n') {
+                self.rest = rest;
+                self.line += 1;
+            }
+        }
+    }
+
+    /// Removes the next word of the current line, quoted words are unquoted.
+    fn word(&mut self) -> Result<Option<&'a str>, &'static str> {
+        self.skip_blanks();
+        if let Some(hashes) = raw_start(self.rest) {
+            let body = &self.rest[hashes + 2..];
+            let close = format!("\"{}", "#".repeat(hashes));
+            let end = body.find(&close).ok_or("unterminated quoted word")?;
+            let (word, rest) = (&body[..end], &body[end + close.len()..]);
+            if rest.starts_with(|ch: char| !ch.is_whitespace()) {
+                return Err("expected whitespace after a quoted word");
+            }
+
+            self.line += word.matches('\n').count();
+            self.rest = rest;
+            return Ok(Some(word));
+        }
+
+        let end = self
+            .rest
+            .find(char::is_whitespace)
+            .unwrap_or(self.rest.len());
+        let (word, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Ok(Some(word).filter(|word| !word.is_empty()))
+    }
+
+    /// Removes the rest of the current line, a single quoted word is unquoted.
+    fn rest_of_line(&mut self) -> Result<&'a str, &'static str> {
+        self.skip_blanks();
+        if raw_start(self.rest).is_some() {
+            let word = self.word()?.unwrap_or("");
+            return match self.word()? {
+                None => Ok(word),
+                Some(_) => Err("unexpected word after the requirement"),
+            };
+        }
+
+        let end = self.rest.find('\n').unwrap_or(self.rest.len());
+        let (line, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Ok(line.trim_end())
+    }
+
+    /// Skips whitespace, except line breaks.
+    fn skip_blanks(&mut self) {
+        self.rest = self
+            .rest
+            .trim_start_matches(|ch: char| ch != '\n' && ch.is_whitespace());
+    }
+}
+
+/// Parses the requirement which ends a schema line.
+fn requirement<'a>(words: &mut Words<'a>) -> Result<Required<'a>, &'static str> {
+    let req = match words.word()? {
+        Some("required") => Required::Required,
+        Some("optional") => Required::Optional,
+        Some("if") => match words.rest_of_line()? {
+            "" => return Err("missing condition"),
+            cond => return Ok(Required::If(cond)),
+        },
+        _ => return Err("expected `required`, `optional` or `if <condition>`"),
+    };
+
+    match words.word()? {
+        None => Ok(req),
+        Some(_) => Err("unexpected word after the requirement"),
+    }
+}
+
+/// Parses `-s` or `--long`.
+fn name(word: &str) -> Result<help::Name<'_>, &'static str> {
+    let mut chars = word.chars();
+    match (
+        word.strip_prefix("--"),
+        chars.next(),
+        chars.next(),
+        chars.next(),
+    ) {
+        (Some(long), ..) if !long.is_empty() => Ok(help::Name::Long(long)),
+        (None, Some('-'), Some(short), None) => Ok(help::Name::Short(short)),
+        _ => Err("expected `-s` or `--long`"),
+    }
+}
+
+/// Error occured when [parsing](Schema::parse) a schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchemaError {
+    /// Line number, starting from `1`.
+    pub line: usize,
+    /// What is wrong with the line.
+    pub reason: &'static str,
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid schema at line {}: {}", self.line, self.reason)
+    }
+}
+
+impl error::Error for SchemaError {}
+
+/// Returns the short name and short aliases of `opt`.
+fn short_names<'o>(opt: &'o Opt<'_>) -> impl Iterator<Item = char> + 'o {
+    let aliases = opt.aliases.iter().filter_map(|alias| match alias.name {
+        help::Name::Short(short) => Some(short),
+        help::Name::Long(_) => None,
+    });
+    opt.short.into_iter().chain(aliases)
+}
+
+/// Returns `Some(true)` if `new` is required in more cases than `old` and `Some(false)` if in
+/// less cases. A changed condition counts as more cases.
+fn stricter(old: &Required<'_>, new: &Required<'_>) -> Option<bool> {
+    match (strictness(old), strictness(new)) {
+        (o, n) if o < n => Some(true),
+        (o, n) if o > n => Some(false),
+        _ => match (old, new) {
+            (Required::If(o), Required::If(n)) if o != n => Some(true),
+            _ => None,
+        },
+    }
+}
+
+fn strictness(req: &Required<'_>) -> u8 {
+    match req {
+        Required::Optional => 0,
+        Required::If(_) => 1,
+        Required::Required => 2,
+    }
+}
+
+fn kind_name(kind: &Kind<'_>) -> &'static str {
+    match kind {
+        Kind::Value { .. } => "a value",
+        Kind::Flag => "a flag",
+        Kind::Count => "a counter",
+    }
+}
+
+/// Displays the most specific name of an option.
+struct Name<'o, 'a>(&'o Opt<'a>);
+
+impl Display for Name<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.0.short, self.0.long) {
+            (_, Some(long)) => write!(f, "--{}", long),
+            (Some(short), None) => write!(f, "-{}", short),
+            (None, None) => f.write_str("<unnamed>"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::help::{Alias, Description, Kind, Name, Opt, Pos, Required};
+
+    use super::{diff, diff_schemas, Change, Schema, SchemaError};
+
+    const fn flag(short: Option<char>, long: Option<&'static str>) -> Opt<'static> {
+        Opt {
+            short,
+            long,
+            kind: Kind::Flag,
+            descr: "",
+            required: Required::Optional,
+            ..Opt::DEFAULT
+        }
+    }
+
+    const OLD: &[Opt<'static>] = &[
+        flag(Some('v'), Some("verbose")),
+        flag(Some('q'), None),
+        flag(None, Some("dry-run")),
+        flag(Some('n'), Some("lines")),
+        flag(None, Some("force")),
+    ];
+
+    const NEW: &[Opt<'static>] = &[
+        flag(Some('v'), Some("version")),
+        Opt {
+            kind: Kind::Count,
+            ..flag(None, Some("verbose"))
+        },
+        flag(Some('q'), Some("quiet")),
+        Opt {
+            kind: Kind::Value {
+                name: None,
+                default: None,
+            },
+            ..flag(Some('n'), Some("lines"))
+        },
+        Opt {
+            required: Required::Required,
+            ..flag(None, Some("force"))
+        },
+        Opt {
+            required: Required::Required,
+            ..flag(None, Some("output"))
+        },
+        flag(Some('d'), Some("debug")),
+    ];
+
+    const FILE: Pos<'static> = Pos {
+        name: "file",
+        descr: "",
+        ..Pos::DEFAULT
+    };
+
+    #[test]
+    fn breaking_and_additive() {
+        let report = diff(
+            &Description::DEFAULT.positionals(&[FILE]).options(OLD),
+            &Description::DEFAULT.options(NEW),
+        );
+
+        assert_eq!(
+            report.breaking,
+            [
+                Change::ShortRemoved {
+                    old: OLD[0],
+                    new: NEW[1],
+                },
+                Change::ShortReassigned {
+                    short: 'v',
+                    old: OLD[0],
+                    new: NEW[0],
+                },
+                Change::OptionRemoved(OLD[2]),
+                Change::KindChanged {
+                    old: OLD[3],
+                    new: NEW[3],
+                },
+                Change::BecameRequired {
+                    old: OLD[4],
+                    new: NEW[4],
+                },
+                Change::OptionAdded(NEW[5]),
+                Change::PositionalRemoved {
+                    index: 0,
+                    old: FILE,
+                },
+            ]
+        );
+        assert_eq!(
+            report.additive,
+            [
+                Change::KindChanged {
+                    old: OLD[0],
+                    new: NEW[1],
+                },
+                Change::LongAdded {
+                    old: OLD[1],
+                    new: NEW[2],
+                },
+                Change::OptionAdded(NEW[0]),
+                Change::OptionAdded(NEW[6]),
+            ]
+        );
+    }
+
+    #[test]
+    fn aliases() {
+        const OLD: &[Opt<'static>] = &[Opt {
+            aliases: &[Alias {
+                name: Name::Long("colour"),
+                visible: true,
+            }],
+            ..flag(None, Some("color"))
+        }];
+        const NEW: &[Opt<'static>] = &[Opt {
+            aliases: &[Alias {
+                name: Name::Short('C'),
+                visible: false,
+            }],
+            ..flag(None, Some("color"))
+        }];
+
+        let report = diff(
+            &Description::DEFAULT.options(OLD),
+            &Description::DEFAULT.options(NEW),
+        );
+        assert_eq!(
+            report.breaking,
+            [Change::AliasRemoved {
+                name: Name::Long("colour"),
+                old: OLD[0],
+                new: NEW[0],
+            }]
+        );
+        assert_eq!(
+            report.additive,
+            [Change::AliasAdded {
+                name: Name::Short('C'),
+                old: OLD[0],
+                new: NEW[0],
+            }]
+        );
+    }
+
+    #[test]
+    fn renamed_with_alias() {
+        const OLD: &[Opt<'static>] = &[flag(Some('c'), Some("colour"))];
+        const NEW: &[Opt<'static>] = &[Opt {
+            aliases: &[
+                Alias {
+                    name: Name::Long("colour"),
+                    visible: false,
+                },
+                Alias {
+                    name: Name::Short('c'),
+                    visible: false,
+                },
+            ],
+            ..flag(Some('C'), Some("color"))
+        }];
+
+        let report = diff(
+            &Description::DEFAULT.options(OLD),
+            &Description::DEFAULT.options(NEW),
+        );
+        assert!(report.breaking.is_empty(), "{}", report);
+        assert_eq!(
+            report.additive,
+            [
+                Change::ShortAdded {
+                    old: OLD[0],
+                    new: NEW[0],
+                },
+                Change::LongAdded {
+                    old: OLD[0],
+                    new: NEW[0],
+                },
+            ]
+        );
+
+        // without the alias `--colour` is gone
+        const COLOR: &[Opt<'static>] = &[flag(Some('c'), Some("color"))];
+        let report = diff(
+            &Description::DEFAULT.options(OLD),
+            &Description::DEFAULT.options(COLOR),
+        );
+        assert_eq!(report.breaking.len(), 1);
+        assert_eq!(
+            report.breaking[0].to_string(),
+            "long name `--colour` was changed to `--color`"
+        );
+    }
+
+    #[test]
+    fn long_removed() {
+        const OLD: &[Opt<'static>] = &[flag(Some('v'), Some("verbose"))];
+        const NEW: &[Opt<'static>] = &[flag(Some('v'), None)];
+
+        let report = diff(
+            &Description::DEFAULT.options(OLD),
+            &Description::DEFAULT.options(NEW),
+        );
+        assert_eq!(
+            report.breaking,
+            [Change::LongRemoved {
+                old: OLD[0],
+                new: NEW[0],
+            }]
+        );
+        assert!(report.additive.is_empty());
+        assert_eq!(
+            report.breaking[0].to_string(),
+            "long name `--verbose` of `-v` was removed"
+        );
+    }
+
+    #[test]
+    fn positional_added() {
+        const REQUIRED: Pos<'static> = Pos {
+            name: "dst",
+            required: Required::Required,
+            ..FILE
+        };
+
+        let report = diff(
+            &Description::DEFAULT.positionals(&[FILE]),
+            &Description::DEFAULT.positionals(&[FILE, FILE, REQUIRED]),
+        );
+        assert_eq!(
+            report.breaking,
+            [Change::PositionalAdded {
+                index: 2,
+                new: REQUIRED,
+            }]
+        );
+        assert_eq!(
+            report.additive,
+            [Change::PositionalAdded {
+                index: 1,
+                new: FILE,
+            }]
+        );
+    }
+
+    #[test]
+    fn schema() {
+        const OPTS: &[Opt<'static>] = &[
+            Opt {
+                aliases: &[Alias {
+                    name: Name::Long("colour"),
+                    visible: false,
+                }],
+                kind: Kind::Value {
+                    name: Some("when"),
+                    default: None,
+                },
+                required: Required::If("--paint is given"),
+                ..flag(Some('c'), Some("color"))
+            },
+            flag(Some('q'), None),
+        ];
+        const POS: &[Pos<'static>] = &[
+            FILE,
+            Pos {
+                required: Required::Required,
+                ..FILE
+            },
+        ];
+
+        let descr = Description::DEFAULT.positionals(POS).options(OPTS);
+        let text = Schema::new(&descr).to_string();
+        assert_eq!(
+            text,
+            "vvvv-schema 1\n\
+             positional file optional\n\
+             positional file required\n\
+             option -c --color alias:--colour value if --paint is given\n\
+             option -q flag optional\n"
+        );
+
+        let schema = Schema::parse(&text).unwrap();
+        assert_eq!(schema, Schema::new(&descr));
+        assert!(diff_schemas(&schema, &Schema::new(&descr)).is_empty());
+
+        let new = Schema::new(&Description::DEFAULT.options(&OPTS[1..]));
+        let report = diff_schemas(&schema, &new);
+        assert_eq!(report.breaking.len(), 3);
+        assert!(report.additive.is_empty());
+
+        for (text, line) in [
+            ("", 1),
+            ("# comment\n\nvvvv-schema 2", 3),
+            ("vvvv-schema 1\noption --a", 2),
+            ("vvvv-schema 1\noption -ab flag optional", 2),
+            ("vvvv-schema 1\noption -a -b flag optional", 2),
+            ("vvvv-schema 1\n\npositional a if", 3),
+            ("vvvv-schema 1\npositional a optional b", 2),
+            ("vvvv-schema 1\nflag -a", 2),
+        ] {
+            assert!(
+                matches!(Schema::parse(text), Err(SchemaError { line: l, .. }) if l == line),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn positional_required() {
+        const REQUIRED: Pos<'static> = Pos {
+            required: Required::Required,
+            ..FILE
+        };
+
+        let report = diff(
+            &Description::DEFAULT.positionals(&[FILE, REQUIRED]),
+            &Description::DEFAULT.positionals(&[REQUIRED, FILE]),
+        );
+        assert_eq!(
+            report.breaking,
+            [Change::PositionalBecameRequired {
+                index: 0,
+                old: FILE,
+                new: REQUIRED,
+            }]
+        );
+        assert_eq!(
+            report.additive,
+            [Change::PositionalBecameOptional {
+                index: 1,
+                old: REQUIRED,
+                new: FILE,
+            }]
+        );
+    }
+
+    #[test]
+    fn schema_quoting() {
+        const OPTS: &[Opt<'static>] = &[
+            Opt {
+                required: Required::If(" multi\nline "),
+                ..flag(None, Some("with space"))
+            },
+            Opt {
+                required: Required::If(r#"r"quoted""#),
+                ..flag(None, Some(r#"say "hi""#))
+            },
+        ];
+        const POS: &[Pos<'static>] = &[
+            Pos { name: "", ..FILE },
+            Pos {
+                name: r#"r"x""#,
+                required: Required::If("plain"),
+                ..FILE
+            },
+        ];
+
+        let descr = Description::DEFAULT.positionals(POS).options(OPTS);
+        let text = Schema::new(&descr).to_string();
+        assert_eq!(
+            text,
+            concat!(
+                "vvvv-schema 1\n",
+                "positional r\"\" optional\n",
+                "positional r#\"r\"x\"\"# if plain\n",
+                "option r\"--with space\" flag if r\" multi\nline \"\n",
+                "option r#\"--say \"hi\"\"# flag if r#\"r\"quoted\"\"#\n",
+            )
+        );
+        assert_eq!(Schema::parse(&text), Ok(Schema::new(&descr)));
+
+        for (text, line) in [
+            ("vvvv-schema 1\npositional r\"a\nb optional", 2),
+            ("vvvv-schema 1\npositional r\"a\nb\"x optional", 2),
+            ("vvvv-schema 1\npositional r\"a\nb\" optional\nflag", 4),
+            ("vvvv-schema 1\npositional a if r\"b\" c", 2),
+        ] {
+            assert!(
+                matches!(Schema::parse(text), Err(SchemaError { line: l, .. }) if l == line),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn raw_is_never_reported() {
+        let typed = Description::DEFAULT.options(&OLD[..1]);
+        assert!(diff(&Description::Raw("help"), &typed).is_empty());
+        assert!(diff(&typed, &Description::None).is_empty());
+    }
+}
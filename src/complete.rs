@@ -0,0 +1,380 @@
+//! Shell completion scripts generated from [typed descriptions](Description::Typed).
+//!
+//! ```
+//! use vvvv::{
+//!     complete::{self, Shell},
+//!     help::{Description, Opt},
+//! };
+//!
+//! let descr = Description::DEFAULT.name("cp").options(&[Opt {
+//!     short: Some('v'),
+//!     long: Some("verbose"),
+//!     descr: "explain what is being done",
+//!     ..Opt::DEFAULT
+//! }]);
+//!
+//! let mut script = String::new();
+//! complete::render(&descr, Shell::Fish, &mut script).unwrap();
+//! assert_eq!(
+//!     script,
+//!     "# Options\ncomplete -c cp -s v -l verbose -d 'explain what is being done'\n",
+//! );
+//! ```
+//!
+//! Options are completed with their names and visible aliases, grouped like in the help: options
+//! without a section come first, then named [sections](Opt::section) in their order. Zsh
+//! completes each group with its own tag and heading, so they are listed separately with
+//! `zstyle ':completion:*' group-name ''`. Bash and fish can't show groups, their scripts list
+//! options in the same order with a comment per group.
+//! Values of options and positional arguments are completed as file names. Hidden options are
+//! left out.
+//!
+//! The script completes the [name](Description::Typed::name) of the description, which has to be
+//! set.
+
+use std::fmt;
+
+use crate::{
+    help::{self, Description, Kind, Name, Opt},
+    shell,
+};
+
+/// Shell to generate a completion script for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    /// Script to `source`, registers the completion with `complete -F`.
+    Bash,
+    /// Script to put into a directory of `$fpath` as `_<name>`.
+    Zsh,
+    /// Script to put into `~/.config/fish/completions/<name>.fish`.
+    Fish,
+}
+
+/// Renders the completion script of `descr` for `shell` to `out`.
+///
+/// Writes nothing if `descr` is not [typed](Description::Typed).
+pub fn render(descr: &Description<'_>, shell: Shell, out: &mut dyn fmt::Write) -> fmt::Result {
+    let (name, sections, positionals, options) = match descr {
+        Description::Typed {
+            name,
+            sections,
+            positionals,
+            options,
+            ..
+        } => (*name, *sections, *positionals, *options),
+        _ => return Ok(()),
+    };
+
+    let options: Vec<_> = options.iter().filter(|opt| !opt.hidden).copied().collect();
+    let mut groups = Vec::new();
+    for section in Some(None).into_iter().chain(
+        help::section_order(sections, positionals, &options)
+            .into_iter()
+            .map(Some),
+    ) {
+        let group: Vec<_> = options
+            .iter()
+            .filter(|opt| opt.section == section)
+            .copied()
+            .collect();
+        if !group.is_empty() {
+            groups.push(Group {
+                section,
+                options: group,
+            });
+        }
+    }
+
+    match shell {
+        Shell::Bash => bash(out, name, &groups),
+        Shell::Zsh => zsh(out, name, &groups),
+        Shell::Fish => fish(out, name, &groups),
+    }
+}
+
+/// Options under a heading of the help.
+struct Group<'a> {
+    section: Option<&'a str>,
+    options: Vec<Opt<'a>>,
+}
+
+impl Group<'_> {
+    fn heading(&self) -> &str {
+        self.section.unwrap_or("Options")
+    }
+}
+
+/// Returns names of an option and its visible aliases.
+fn names<'o, 'a>(opt: &'o Opt<'a>) -> impl Iterator<Item = Name<'a>> + 'o {
+    let aliases = opt.aliases.iter().filter(|alias| alias.visible);
+    opt.short
+        .map(Name::Short)
+        .into_iter()
+        .chain(opt.long.map(Name::Long))
+        .chain(aliases.map(|alias| alias.name))
+}
+
+/// Returns the name of the shell function which completes `name`.
+fn function(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect();
+    format!("_{}", name)
+}
+
+fn bash(out: &mut dyn fmt::Write, name: &str, groups: &[Group<'_>]) -> fmt::Result {
+    let function = function(name);
+    writeln!(out, "{}() {{", function)?;
+    out.write_str("    local cur=${COMP_WORDS[COMP_CWORD]} prev=${COMP_WORDS[COMP_CWORD-1]}\n")?;
+
+    let values: Vec<_> = groups
+        .iter()
+        .flat_map(|group| &group.options)
+        .filter(|opt| matches!(opt.kind, Kind::Value { .. }))
+        .flat_map(|opt| names(opt).map(|name| shell::quote(&name.to_string()).into_owned()))
+        .collect();
+    if !values.is_empty() {
+        writeln!(out, "    case $prev in\n        {})", values.join("|"))?;
+        out.write_str("            COMPREPLY=($(compgen -f -- \"$cur\"))\n")?;
+        out.write_str("            return\n            ;;\n    esac\n")?;
+    }
+
+    out.write_str("    local opts=(\n")?;
+    for group in groups {
+        writeln!(out, "        # {}", group.heading())?;
+        for opt in &group.options {
+            let names: Vec<_> = names(opt).map(|name| name.to_string()).collect();
+            writeln!(out, "        {}", shell::join(&names))?;
+        }
+    }
+    out.write_str("    )\n")?;
+
+    out.write_str("    if [[ $cur == -* ]]; then\n")?;
+    out.write_str("        COMPREPLY=($(compgen -W \"${opts[*]}\" -- \"$cur\"))\n")?;
+    out.write_str("    else\n        COMPREPLY=($(compgen -f -- \"$cur\"))\n    fi\n}\n")?;
+    writeln!(out, "complete -F {} {}", function, shell::quote(name))
+}
+
+fn zsh(out: &mut dyn fmt::Write, name: &str, groups: &[Group<'_>]) -> fmt::Result {
+    let function = function(name);
+    writeln!(out, "#compdef {}\n\n{}() {{", name, function)?;
+
+    let values: Vec<_> = groups
+        .iter()
+        .flat_map(|group| &group.options)
+        .filter(|opt| matches!(opt.kind, Kind::Value { .. }))
+        .flat_map(|opt| names(opt).map(|name| shell::quote(&name.to_string()).into_owned()))
+        .collect();
+    if !values.is_empty() {
+        writeln!(
+            out,
+            "    case $words[CURRENT-1] in\n        {})",
+            values.join("|")
+        )?;
+        out.write_str("            _files\n            return\n            ;;\n    esac\n")?;
+    }
+
+    out.write_str("    if [[ $PREFIX != -* ]]; then\n        _files\n        return\n    fi\n")?;
+    for group in groups {
+        out.write_str("\n    local -a opts\n    opts=(\n")?;
+        for opt in &group.options {
+            // `:` separates the name from the description in `_describe`
+            let descr = opt.descr.lines().next().unwrap_or("").replace(':', "\\:");
+            for name in names(opt) {
+                let item = format!("{}:{}", name, descr);
+                writeln!(out, "        {}", shell::quote(&item))?;
+            }
+        }
+        out.write_str("    )\n")?;
+
+        let tag = match group.section {
+            Some(section) => format!("{}-options", tag(section)),
+            None => "options".to_owned(),
+        };
+        let title = shell::quote(group.heading());
+        writeln!(out, "    _describe -t {} {} opts", tag, title)?;
+    }
+
+    writeln!(out, "}}\n\n{} \"$@\"", function)
+}
+
+/// Turns a heading into a zsh tag, i.e. `Network access` into `network-access`.
+fn tag(heading: &str) -> String {
+    heading
+        .chars()
+        .map(|ch| match ch.is_ascii_alphanumeric() {
+            true => ch.to_ascii_lowercase(),
+            false => '-',
+        })
+        .collect()
+}
+
+fn fish(out: &mut dyn fmt::Write, name: &str, groups: &[Group<'_>]) -> fmt::Result {
+    let name = shell::quote(name);
+    for group in groups {
+        writeln!(out, "# {}", group.heading())?;
+        for opt in &group.options {
+            write!(out, "complete -c {}", name)?;
+            for name in names(opt) {
+                match name {
+                    Name::Short(short) => write!(out, " -s {}", shell::quote(&short.to_string()))?,
+                    Name::Long(long) => write!(out, " -l {}", shell::quote(long))?,
+                }
+            }
+            if let Kind::Value { .. } = opt.kind {
+                out.write_str(" -r")?;
+            }
+
+            let descr = opt.descr.lines().next().unwrap_or("");
+            if !descr.is_empty() {
+                write!(out, " -d {}", fish_quote(descr))?;
+            }
+            out.write_str("\n")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Quotes `text` for fish, which unlike a POSIX shell escapes `\` and `'` inside single quotes.
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, Shell};
+    use crate::help::{Alias, Description, Kind, Name, Opt, Pos};
+
+    const DESCR: Description<'static> = Description::DEFAULT
+        .name("fetch")
+        .sections(&["Network"])
+        .positionals(&[Pos {
+            name: "url",
+            ..Pos::DEFAULT
+        }])
+        .options(&[
+            Opt {
+                short: Some('o'),
+                kind: Kind::Value {
+                    name: Some("file"),
+                    default: None,
+                },
+                descr: "where to write",
+                ..Opt::DEFAULT
+            },
+            Opt {
+                long: Some("proxy"),
+                kind: Kind::Value {
+                    name: Some("url"),
+                    default: None,
+                },
+                descr: "proxy: host and port",
+                section: Some("Network"),
+                aliases: &[Alias {
+                    name: Name::Long("via"),
+                    visible: true,
+                }],
+                ..Opt::DEFAULT
+            },
+            Opt {
+                long: Some("offline"),
+                descr: "don't use the network",
+                section: Some("Network"),
+                ..Opt::DEFAULT
+            },
+            Opt {
+                long: Some("debug"),
+                hidden: true,
+                ..Opt::DEFAULT
+            },
+        ]);
+
+    fn script(shell: Shell) -> String {
+        let mut out = String::new();
+        render(&DESCR, shell, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn bash() {
+        assert_eq!(
+            script(Shell::Bash),
+            r#"_fetch() {
+    local cur=${COMP_WORDS[COMP_CWORD]} prev=${COMP_WORDS[COMP_CWORD-1]}
+    case $prev in
+        -o|--proxy|--via)
+            COMPREPLY=($(compgen -f -- "$cur"))
+            return
+            ;;
+    esac
+    local opts=(
+        # Options
+        -o
+        # Network
+        --proxy --via
+        --offline
+    )
+    if [[ $cur == -* ]]; then
+        COMPREPLY=($(compgen -W "${opts[*]}" -- "$cur"))
+    else
+        COMPREPLY=($(compgen -f -- "$cur"))
+    fi
+}
+complete -F _fetch fetch
+"#
+        );
+    }
+
+    #[test]
+    fn zsh() {
+        assert_eq!(
+            script(Shell::Zsh),
+            r#"#compdef fetch
+
+_fetch() {
+    case $words[CURRENT-1] in
+        -o|--proxy|--via)
+            _files
+            return
+            ;;
+    esac
+    if [[ $PREFIX != -* ]]; then
+        _files
+        return
+    fi
+
+    local -a opts
+    opts=(
+        '-o:where to write'
+    )
+    _describe -t options Options opts
+
+    local -a opts
+    opts=(
+        '--proxy:proxy\: host and port'
+        '--via:proxy\: host and port'
+        '--offline:don'\''t use the network'
+    )
+    _describe -t network-options Network opts
+}
+
+_fetch "$@"
+"#
+        );
+    }
+
+    #[test]
+    fn fish() {
+        assert_eq!(
+            script(Shell::Fish),
+            r#"# Options
+complete -c fetch -s o -r -d 'where to write'
+# Network
+complete -c fetch -l proxy -l via -r -d 'proxy: host and port'
+complete -c fetch -l offline -d 'don\'t use the network'
+"#
+        );
+    }
+}
@@ -1,9 +1,10 @@
 use std::{convert::Infallible, fmt::Display};
 
-use crate::{tr::IntoOwned, OwnToken, Token};
+use crate::{tr::IntoOwned, OwnToken, Prefix, Token};
 
 /// Error occured when parsing command line arguments.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error<'a, C = Infallible> {
     /// Unknown option. i.e. `-x` wass provided, but not expected.
     UnknownOption(Token<'a>),
@@ -21,6 +22,9 @@ pub enum Error<'a, C = Infallible> {
     RequiredOption(&'static str), // TODO: may not be strign
     /// Too many options caused an overflow of the counter. I.e. counter type is `u8` and user provided `-vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv`.
     TooManyOptions(Token<'a>),
+    /// Abbreviated long option matches several options. I.e. `--ver` was provided, but there are
+    /// both `--verbose` and `--version`, see [`ParseConfig::abbreviations`](crate::ParseConfig::abbreviations).
+    AmbiguousOption(Token<'a>, Vec<&'static str>),
     /// Custom error. Usually this represents parsing errors (e.g. `<i32 as FromStr>::Err | <PathBuf as FromStr>::Err`).
     Custom(C),
 }
@@ -38,6 +42,7 @@ impl<'a, E> IntoOwned for Error<'a, E> {
             Error::UnexpectedPositional(t) => OwnError::UnexpectedPositional(t.into_owned()),
             Error::RequiredOption(t) => OwnError::RequiredOption(t),
             Error::TooManyOptions(t) => OwnError::TooManyOptions(t.into_owned()),
+            Error::AmbiguousOption(t, c) => OwnError::AmbiguousOption(t.into_owned(), c),
             Error::Custom(t) => OwnError::Custom(t),
         }
     }
@@ -58,6 +63,21 @@ impl<C: Display> Display for Error<'_, C> {
             }
             Error::RequiredOption(opt) => write!(f, "Required option `{}` was not provided", opt),
             Error::TooManyOptions(token) => write!(f, "Too many options: `{}`", token),
+            Error::AmbiguousOption(token, candidates) => {
+                write!(f, "Ambiguous option: `{}`, could be", token)?;
+                let dashes = match token {
+                    Token::Keyed {
+                        prefix: Prefix::SingleDash,
+                        ..
+                    } => "-",
+                    _ => "--",
+                };
+                for (i, candidate) in candidates.iter().enumerate() {
+                    let sep = if i == 0 { "" } else { "," };
+                    write!(f, "{} `{}{}`", sep, dashes, candidate)?;
+                }
+                Ok(())
+            }
             Error::Custom(custom) => custom.fmt(f),
         }
     }
@@ -70,6 +90,7 @@ impl<'a, E> From<Error<'a, E>> for OwnError<E> {
 }
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum OwnError<C = Infallible> {
     /// Unknown option. i.e. `-x` wass provided, but not expected.
     UnknownOption(OwnToken),
@@ -87,13 +108,16 @@ pub enum OwnError<C = Infallible> {
     RequiredOption(&'static str), // TODO: may not be strign
     /// Too many options caused an overflow of the counter. I.e. counter type is `u8` and user provided `-vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv -vvvvvvvvvvvvvvvv`.
     TooManyOptions(OwnToken),
+    /// Abbreviated long option matches several options. I.e. `--ver` was provided, but there are
+    /// both `--verbose` and `--version`, see [`ParseConfig::abbreviations`](crate::ParseConfig::abbreviations).
+    AmbiguousOption(OwnToken, Vec<&'static str>),
     /// Custom error. Usually this represents parsing errors (e.g. `<i32 as FromStr>::Err | <PathBuf as FromStr>::Err`).
     Custom(C),
 }
 
 impl<C> OwnError<C> {
     /// Borrow owned error as borrowed error.
-    pub fn borrow(&self) -> Error<C>
+    pub fn borrow(&self) -> Error<'_, C>
     where
         C: Clone,
     {
@@ -104,14 +128,17 @@ impl<C> OwnError<C> {
             Self::UnexpectedValue(token) => Error::UnexpectedValue(token.borrow()),
             Self::ExpectedPositional(token) => Error::ExpectedPositional(token.borrow()),
             Self::UnexpectedPositional(token) => Error::UnexpectedPositional(token.borrow()),
-            Self::RequiredOption(s) => Error::RequiredOption(*s),
+            Self::RequiredOption(s) => Error::RequiredOption(s),
             Self::TooManyOptions(token) => Error::TooManyOptions(token.borrow()),
+            Self::AmbiguousOption(token, candidates) => {
+                Error::AmbiguousOption(token.borrow(), candidates.clone())
+            }
             Self::Custom(custom) => Error::Custom(custom.clone()),
         }
     }
 
     /// Borrow owned error as borrowed error.
-    fn borrow_(&self) -> Error<C> {
+    fn borrow_(&self) -> Error<'_, C> {
         match self {
             Self::UnknownOption(token) => Error::UnknownOption(token.borrow()),
             Self::UnexpectedMulti(token) => Error::UnexpectedMulti(token.borrow()),
@@ -119,8 +146,11 @@ impl<C> OwnError<C> {
             Self::UnexpectedValue(token) => Error::UnexpectedValue(token.borrow()),
             Self::ExpectedPositional(token) => Error::ExpectedPositional(token.borrow()),
             Self::UnexpectedPositional(token) => Error::UnexpectedPositional(token.borrow()),
-            Self::RequiredOption(s) => Error::RequiredOption(*s),
+            Self::RequiredOption(s) => Error::RequiredOption(s),
             Self::TooManyOptions(token) => Error::TooManyOptions(token.borrow()),
+            Self::AmbiguousOption(token, candidates) => {
+                Error::AmbiguousOption(token.borrow(), candidates.clone())
+            }
             Self::Custom(_) => unreachable!(),
         }
     }
@@ -137,6 +167,80 @@ impl<C: Display> Display for OwnError<C> {
     }
 }
 
+/// Non-fatal problem occured when parsing command line arguments.
+///
+/// Warnings are reported separately from [errors](Error), see
+/// [`FromArgsIter::warnings`](crate::FromArgsIter::warnings) and the `*_with_warnings`
+/// functions, i.e. [`from_args_with_warnings`](crate::from_args_with_warnings).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Warning<'a> {
+    /// Deprecated option was used. I.e. `--colour` was provided, but it's deprecated in favor of
+    /// `--color`.
+    Deprecated {
+        token: Token<'a>,
+        replacement: Option<&'static str>,
+    },
+}
+
+impl Display for Warning<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::Deprecated {
+                token,
+                replacement: Some(r),
+            } => write!(f, "Option `{}` is deprecated, use `{}` instead", token, r),
+            Warning::Deprecated {
+                token,
+                replacement: None,
+            } => write!(f, "Option `{}` is deprecated", token),
+        }
+    }
+}
+
+impl IntoOwned for Warning<'_> {
+    type Owned = OwnWarning;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            Warning::Deprecated { token, replacement } => OwnWarning::Deprecated {
+                token: token.into_owned(),
+                replacement,
+            },
+        }
+    }
+}
+
+/// Owned counterpart of [`Warning`], see [`from_env_with_warnings`](crate::from_env_with_warnings).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OwnWarning {
+    /// Deprecated option was used. I.e. `--colour` was provided, but it's deprecated in favor of
+    /// `--color`.
+    Deprecated {
+        token: OwnToken,
+        replacement: Option<&'static str>,
+    },
+}
+
+impl OwnWarning {
+    /// Borrow owned warning as borrowed warning.
+    pub fn borrow(&self) -> Warning<'_> {
+        match self {
+            Self::Deprecated { token, replacement } => Warning::Deprecated {
+                token: token.borrow(),
+                replacement: *replacement,
+            },
+        }
+    }
+}
+
+impl Display for OwnWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.borrow().fmt(f)
+    }
+}
+
 #[derive(Debug)]
 pub struct SwitchAlreadySetError;
 
@@ -154,3 +258,22 @@ impl Display for TooManyOptionsError {
         f.write_str("Too many options")
     }
 }
+
+/// Tokens can't be turned back into arguments, see [`Token::unparse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnparseError {
+    /// Index of the first token which wouldn't be parsed back.
+    pub index: usize,
+    /// The token itself.
+    pub token: OwnToken,
+}
+
+impl Display for UnparseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Token `{}` at {} can't be represented as arguments",
+            self.token, self.index
+        )
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{FromArgs, Parse, PollInit};
+use crate::{help::Deprecated, Error, FromArgs, Parse, PollInit, Prefix, Token, Warning};
 
 /// Iterator over parse errors and parsed value.
 ///
@@ -7,6 +7,20 @@ use crate::{FromArgs, Parse, PollInit};
 pub struct FromArgsIter<'a, T: FromArgs<'a>, I: Iterator<Item = &'a str>> {
     pub(crate) parser: Parse<'a, I>,
     pub(crate) init: Option<T::Init>,
+    pub(crate) warnings: Vec<Warning<'a>>,
+}
+
+impl<'a, T, I> FromArgsIter<'a, T, I>
+where
+    T: FromArgs<'a>,
+    I: Iterator<Item = &'a str>,
+{
+    /// Returns warnings which occured so far, i.e. uses of deprecated options.
+    ///
+    /// Warnings don't prevent `T` from being created.
+    pub fn warnings(&self) -> &[Warning<'a>] {
+        &self.warnings
+    }
 }
 
 impl<'a, T, I> Iterator for FromArgsIter<'a, T, I>
@@ -21,10 +35,49 @@ where
 
         loop {
             match self.parser.next() {
-                Some(token) => match initializer.poll_init(token) {
-                    Err(err) => return Some(Err(err)),
-                    Ok(()) => (),
-                },
+                Some(token) => {
+                    let abbreviated = match token {
+                        _ if !self.parser.config().accepts_abbreviations() => None,
+                        Token::Long { key, .. } => Some(key),
+                        // `-name` of single dash long options, unless it's a short option
+                        Token::Keyed {
+                            prefix: Prefix::SingleDash,
+                            key,
+                            ..
+                        } if T::DESCRIPTION.find_option(&token).is_none() => Some(key),
+                        _ => None,
+                    };
+
+                    let token = match abbreviated.map(|key| T::DESCRIPTION.resolve_long(key)) {
+                        Some(Ok(Some(key))) => match token {
+                            Token::Long { value, .. } => Token::Long { key, value },
+                            Token::Keyed { prefix, value, .. } => {
+                                Token::Keyed { prefix, key, value }
+                            }
+                            token => token,
+                        },
+                        Some(Err(candidates)) => {
+                            return Some(Err(Error::AmbiguousOption(token, candidates)))
+                        }
+                        _ => token,
+                    };
+
+                    let deprecated = T::DESCRIPTION
+                        .find_option(&token)
+                        .and_then(|opt| opt.deprecated);
+                    if let Some(Deprecated { replacement }) = deprecated {
+                        self.warnings
+                            .push(Warning::Deprecated { token, replacement });
+                    }
+
+                    if let Err(err) = initializer.poll_init(token) {
+                        return Some(Err(err));
+                    }
+
+                    if initializer.stop_options() {
+                        self.parser.stop_options();
+                    }
+                }
                 None => return Some(self.init.take().unwrap().finish()),
             }
         }
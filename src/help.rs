@@ -1,137 +1,419 @@
 use std::{
+    env,
+    fmt::{self, Display, Write as _},
     io::{self, Write},
-    iter::once,
 };
 
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
-
-use crate::dumb_wrap::Wrap;
+use crate::{
+    markup,
+    term::{self, ColorChoice, Stream},
+    wrap::{self, Wrap},
+    Token,
+};
 
+/// Help of a program, also used to [resolve](crate::ParseConfig::resolve) option names and to
+/// [diff](crate::compat::diff) versions of a program.
+///
+/// Typed descriptions are best built from [`Description::DEFAULT`] with the setters, so that new
+/// fields don't break the code:
+///
+/// ```
+/// use vvvv::help::{Description, Opt};
+///
+/// const DESCR: Description = Description::DEFAULT
+///     .name("cp")
+///     .descr("Copies files")
+///     .usage("cp [-v] <src> <dst>")
+///     .options(&[Opt {
+///         short: Some('v'),
+///         descr: "explain what is being done",
+///         ..Opt::DEFAULT
+///     }]);
+/// ```
+#[derive(Debug, Clone, Copy)]
 pub enum Description<'a> {
     None,
     Raw(&'a str),
     Typed {
+        /// Name of the program, used in the generated usage line, templates, man pages and
+        /// completions.
+        name: &'a str,
         descr: &'a str,
-        // TODO: usage probably should be created from `positionals` and `options`
+        /// Description used in the [long help](Mode::Long) instead of `descr`.
+        long_descr: Option<&'a str>,
+        /// Usage line, generated from the other fields if empty, see
+        /// [`Description::usage_line`].
         usage: &'a str,
+        /// Names of sections in the order they are rendered, see [`Opt::section`].
+        ///
+        /// Sections which are used but not listed here are rendered after the listed ones.
+        sections: &'a [&'a str],
         positionals: &'a [Pos<'a>],
         options: &'a [Opt<'a>],
+        /// Worked examples, rendered after the options.
+        examples: &'a [Example<'a>],
+        /// Environment variables which affect the program, rendered after the examples.
+        env: &'a [Env<'a>],
+        /// Text rendered at the end of the help, i.e. "see also" notes or a link to the
+        /// documentation.
+        after_help: Option<&'a str>,
+        /// Template which replaces the default layout.
+        ///
+        /// Placeholders are replaced with parts of the help:
+        ///
+        /// - `{name}`, `{descr}`, `{usage}` and `{after-help}` - the corresponding fields,
+        /// - `{positionals}` and `{options}` - rows of positional arguments and options without
+        ///   a section,
+        /// - `{sections}` - named sections with their headings,
+        /// - `{examples}` and `{env}` - rows of examples and environment variables.
+        ///
+        /// `{{` and `}}` are replaced with `{` and `}`, unknown placeholders are kept as is.
+        /// Text placeholders are wrapped and indented to the column at which they start.
+        ///
+        /// ```
+        /// use vvvv::help::{Description, Opt};
+        ///
+        /// let descr = Description::DEFAULT
+        ///     .name("cp")
+        ///     .descr("Copies files")
+        ///     .usage("cp [-v] <src> <dst>")
+        ///     .options(&[Opt {
+        ///         short: Some('v'),
+        ///         descr: "explain what is being done",
+        ///         ..Opt::DEFAULT
+        ///     }])
+        ///     .after_help("See `man cp` for details.")
+        ///     .template("{name} - {descr}\n\nUSAGE: {usage}\n\n{options}\n{after-help}\n");
+        ///
+        /// assert_eq!(
+        ///     descr.to_string(),
+        ///     "cp - Copies files\n\nUSAGE: cp [-v] <src> <dst>\n\n    -v    explain what is being done\n\nSee `man cp` for details.\n",
+        /// );
+        /// ```
+        template: Option<&'a str>,
     },
 }
 
-impl Description<'_> {
+/// Generates a setter of a [`Description::Typed`] field for each `field: Type => value`, the
+/// first argument lists all fields.
+macro_rules! setters {
+    ([$($all:ident),*]) => {};
+    ([$($all:ident),*] $(#[$attr:meta])* $field:ident: $ty:ty => $value:expr, $($rest:tt)*) => {
+        $(#[$attr])*
+        ///
+        /// Other descriptions are replaced with [`Description::DEFAULT`] first.
+        #[allow(unused_variables)]
+        pub const fn $field(self, $field: $ty) -> Self {
+            let value = $value;
+            match self {
+                Description::Typed { $($all),* } => {
+                    let $field = value;
+                    Description::Typed { $($all),* }
+                }
+                Description::None | Description::Raw(_) => Self::DEFAULT.$field($field),
+            }
+        }
+
+        setters!([$($all),*] $($rest)*);
+    };
+}
+
+impl<'a> Description<'a> {
+    /// Typed description with every field empty, a starting point for the setters below.
+    pub const DEFAULT: Self = Description::Typed {
+        name: "",
+        descr: "",
+        long_descr: None,
+        usage: "",
+        sections: &[],
+        positionals: &[],
+        options: &[],
+        examples: &[],
+        env: &[],
+        after_help: None,
+        template: None,
+    };
+
+    setters! {
+        [
+            name,
+            descr,
+            long_descr,
+            usage,
+            sections,
+            positionals,
+            options,
+            examples,
+            env,
+            after_help,
+            template
+        ]
+        /// Sets the name of the program.
+        name: &'a str => name,
+        /// Sets the description.
+        descr: &'a str => descr,
+        /// Sets the description used in the [long help](Mode::Long).
+        long_descr: &'a str => Some(long_descr),
+        /// Sets the usage line.
+        usage: &'a str => usage,
+        /// Sets the order of sections.
+        sections: &'a [&'a str] => sections,
+        /// Sets the positional arguments.
+        positionals: &'a [Pos<'a>] => positionals,
+        /// Sets the options.
+        options: &'a [Opt<'a>] => options,
+        /// Sets the worked examples.
+        examples: &'a [Example<'a>] => examples,
+        /// Sets the environment variables.
+        env: &'a [Env<'a>] => env,
+        /// Sets the text rendered at the end of the help.
+        after_help: &'a str => Some(after_help),
+        /// Sets the template which replaces the default layout.
+        template: &'a str => Some(template),
+    }
+
+    /// Prints the help to the stdout.
     pub fn print(&self, width_limit: Option<usize>) -> io::Result<()> {
-        self.write_i(&mut io::stdout().lock(), width_limit, 4)
+        let settings = Settings {
+            width_limit,
+            ..Settings::default()
+        };
+        self.write(&mut io::stdout().lock(), &settings)
+    }
+
+    /// Prints the help to the stdout, wrapping lines at the automatically detected width.
+    ///
+    /// See [`auto_width`] for details.
+    pub fn print_auto(&self) -> io::Result<()> {
+        self.write(&mut io::stdout().lock(), &Settings::auto(Stream::Stdout))
+    }
+
+    /// Writes the help to the `writer`.
+    ///
+    /// The help is rendered into a buffer first, so the `writer` receives it in a single
+    /// [`write_all`](Write::write_all) call.
+    pub fn write(&self, writer: &mut dyn Write, settings: &Settings) -> io::Result<()> {
+        let mut buf = String::new();
+        self.render(&mut buf, settings)
+            .expect("writing to a `String` can't fail");
+        writer.write_all(buf.as_bytes())
+    }
+
+    /// Renders the help into a `String` wrapping lines at `width`.
+    pub fn to_string_with_width(&self, width: usize) -> String {
+        let mut buf = String::new();
+        let settings = Settings {
+            width_limit: Some(width),
+            ..Settings::default()
+        };
+        self.render(&mut buf, &settings)
+            .expect("writing to a `String` can't fail");
+        buf
+    }
+
+    /// Returns the option which `token` refers to, if any.
+    pub(crate) fn find_option(&self, token: &Token<'_>) -> Option<&Opt<'a>> {
+        let options = match self {
+            Description::Typed { options, .. } => options,
+            _ => return None,
+        };
+
+        options.iter().find(|opt| opt.matches(token))
+    }
+
+    /// Resolves the possibly abbreviated long option name `key` against options of this
+    /// description, see [`ParseConfig::abbreviations`](crate::ParseConfig::abbreviations).
+    ///
+    /// An exact match wins, otherwise `key` has to be a prefix of long names (or long aliases) of
+    /// a single option. Returns `Ok(None)` if no option matches and the names of all matching
+    /// options if `key` is ambiguous.
+    ///
+    /// ```
+    /// use vvvv::help::{Description, Opt};
+    ///
+    /// let descr = Description::DEFAULT.options(&[
+    ///     Opt { long: Some("verbose"), ..Opt::DEFAULT },
+    ///     Opt { long: Some("version"), ..Opt::DEFAULT },
+    ///     Opt { long: Some("ver"), ..Opt::DEFAULT },
+    /// ]);
+    ///
+    /// assert_eq!(descr.resolve_long("verb"), Ok(Some("verbose")));
+    /// assert_eq!(descr.resolve_long("ver"), Ok(Some("ver")));
+    /// assert_eq!(descr.resolve_long("vers"), Ok(Some("version")));
+    /// assert_eq!(descr.resolve_long("v"), Err(vec!["verbose", "version", "ver"]));
+    /// assert_eq!(descr.resolve_long("x"), Ok(None));
+    /// ```
+    pub fn resolve_long(&self, key: &str) -> Result<Option<&'a str>, Vec<&'a str>> {
+        let options = match self {
+            Description::Typed { options, .. } if !key.is_empty() => *options,
+            _ => return Ok(None),
+        };
+
+        let exact = options
+            .iter()
+            .flat_map(Opt::long_names)
+            .find(|name| *name == key);
+        if exact.is_some() {
+            return Ok(exact);
+        }
+
+        let candidates: Vec<_> = options
+            .iter()
+            .filter_map(|opt| opt.long_names().find(|name| name.starts_with(key)))
+            .collect();
+        match candidates[..] {
+            [] => Ok(None),
+            [name] => Ok(Some(name)),
+            _ => Err(candidates),
+        }
     }
 
-    fn write_i(
+    /// Returns the usage line, `usage` if it's not empty or a line generated from the name,
+    /// options and positional arguments.
+    ///
+    /// The generated line lists required options by name and sums up other options as
+    /// `[options]` and `[<section> options]`, in the order of their [sections](Opt::section).
+    /// Hidden options are left out.
+    ///
+    /// ```
+    /// use vvvv::help::{Description, Kind, Opt, Pos, Required};
+    ///
+    /// let descr = Description::DEFAULT
+    ///     .name("cp")
+    ///     .positionals(&[
+    ///         Pos { name: "src", required: Required::Required, ..Pos::DEFAULT },
+    ///         Pos { name: "dst", ..Pos::DEFAULT },
+    ///     ])
+    ///     .options(&[
+    ///         Opt { short: Some('v'), kind: Kind::Count, ..Opt::DEFAULT },
+    ///         Opt {
+    ///             long: Some("target"),
+    ///             kind: Kind::Value { name: Some("dir"), default: None },
+    ///             required: Required::Required,
+    ///             ..Opt::DEFAULT
+    ///         },
+    ///         Opt { long: Some("backup"), section: Some("Output"), ..Opt::DEFAULT },
+    ///     ]);
+    ///
+    /// assert_eq!(descr.usage_line(), "cp --target <dir> [options] [Output options] <src> [<dst>]");
+    /// assert_eq!(descr.usage("cp [-v] <src> [<dst>]").usage_line(), "cp [-v] <src> [<dst>]");
+    /// ```
+    pub fn usage_line(&self) -> String {
+        let (name, usage, sections, positionals, options) = match self {
+            Description::Typed {
+                name,
+                usage,
+                sections,
+                positionals,
+                options,
+                ..
+            } => (name, usage, sections, positionals, options),
+            _ => return String::new(),
+        };
+        if !usage.is_empty() {
+            return (*usage).to_owned();
+        }
+
+        let options: Vec<_> = options.iter().filter(|opt| !opt.hidden).copied().collect();
+        let sections = section_order(sections, positionals, &options);
+        let mut words: Vec<_> = Some(name.to_string())
+            .filter(|name| !name.is_empty())
+            .into_iter()
+            .collect();
+
+        for section in Some(None).into_iter().chain(sections.into_iter().map(Some)) {
+            let mut optional = false;
+            for opt in options.iter().filter(|opt| opt.section == section) {
+                match (opt.required, opt.usage_name()) {
+                    (Required::Required, Some(name)) => words.push(name),
+                    _ => optional = true,
+                }
+            }
+
+            if optional {
+                words.push(match section {
+                    Some(section) => format!("[{} options]", section),
+                    None => "[options]".to_owned(),
+                });
+            }
+        }
+
+        for pos in *positionals {
+            words.push(match pos.required {
+                Required::Required => format!("<{}>", pos.name),
+                _ => format!("[<{}>]", pos.name),
+            });
+        }
+
+        words.join(" ")
+    }
+
+    /// Renders the help to the `out`.
+    pub fn render(&self, out: &mut dyn fmt::Write, settings: &Settings) -> fmt::Result {
+        self.render_with(&DefaultRenderer, out, settings)
+    }
+
+    /// Renders the help to the `out` using a custom `renderer`.
+    pub fn render_with(
         &self,
-        writer: &mut dyn Write,
-        width_limit: Option<usize>,
-        indent: usize,
-    ) -> io::Result<()> {
+        renderer: &dyn HelpRenderer,
+        out: &mut dyn fmt::Write,
+        settings: &Settings,
+    ) -> fmt::Result {
         match self {
             Description::None => Ok(()),
-            Description::Raw(s) => writer.write_all(s.as_bytes()),
+            Description::Raw(s) => out.write_str(s),
             Description::Typed {
+                name,
                 descr,
-                usage,
+                long_descr,
+                sections,
                 positionals,
                 options,
+                examples,
+                env,
+                after_help,
+                template,
+                ..
             } => {
-                let limit = width_limit.unwrap_or(usize::MAX);
-
-                // here starts the 'ugh' part
-
-                for it in Wrap::new(limit, once(*descr)) {
-                    match it {
-                        crate::dumb_wrap::Item::Part(p) => writer.write_all(p.as_bytes())?,
-                        crate::dumb_wrap::Item::Break(l) => {
-                            writer.write_all(l.as_bytes())?;
-                            writer.write_all(b"\n")?;
-                        }
-                    }
+                let parts = Parts::new(renderer, settings, sections, positionals, options, None)?
+                    .extra(examples, env);
+                let descr = settings.mode.pick(descr, *long_descr);
+
+                if let Some(template) = template {
+                    let mut out = Column { out, col: 0 };
+                    let usage = self.usage_line();
+                    return parts.template(&mut out, template, name, descr, &usage, *after_help);
                 }
 
-                writer.write_all(b"\n\nUsage:\n  ")?;
+                parts.markup(out, descr, 0)?;
+                out.write_str("\n\n")?;
 
-                for it in Wrap::new(limit - 2, once(*usage)) {
-                    match it {
-                        crate::dumb_wrap::Item::Part(p) => writer.write_all(p.as_bytes())?,
-                        crate::dumb_wrap::Item::Break(l) => {
-                            writer.write_all(l.as_bytes())?;
-                            writer.write_all(b"\n  ")?;
-                        }
-                    }
+                renderer.heading(out, settings, Heading::Usage)?;
+                out.write_str("\n  ")?;
+                parts.text(out, &self.usage_line(), 2)?;
+                out.write_char('\n')?;
+
+                parts.body(out)?;
+
+                if !examples.is_empty() {
+                    out.write_char('\n')?;
+                    renderer.heading(out, settings, Heading::Examples)?;
+                    out.write_char('\n')?;
+                    parts.examples(out)?;
                 }
 
-                // 64 spaces (I don't belive in indentension that takes more than 80% of default term)
-                const SPACES: &[u8] =
-                    b"                                                                ";
-
-                if !positionals.is_empty() {
-                    writer.write_all(b"\n\nPositional arguments:\n")?;
-                    let max = positionals
-                        .iter()
-                        .map(|pos| pos.name.width())
-                        .max()
-                        .unwrap_or(0);
-                    let descr_ind = indent + max + indent;
-
-                    for pos in *positionals {
-                        writer.write_all(&SPACES[..indent])?;
-                        writer.write_all(pos.name.as_bytes())?;
-                        writer.write_all(&SPACES[..(descr_ind - pos.name.width() - indent)])?;
-
-                        for x in Wrap::new(limit - descr_ind, once(pos.descr).chain(once("\n"))) {
-                            match x {
-                                crate::dumb_wrap::Item::Part(p) => {
-                                    writer.write_all(p.as_bytes())?
-                                }
-                                crate::dumb_wrap::Item::Break(l) => {
-                                    writer.write_all(l.as_bytes())?;
-                                    writer.write_all(b"\n")?;
-                                    writer.write_all(&SPACES[..(descr_ind)])?;
-                                }
-                            }
-                        }
-                    }
+                if !env.is_empty() {
+                    out.write_char('\n')?;
+                    renderer.heading(out, settings, Heading::Environment)?;
+                    out.write_char('\n')?;
+                    parts.env(out)?;
                 }
 
-                if !options.is_empty() {
-                    writer.write_all(b"\n\nOptions:\n")?;
-                    let max = options.iter().map(|opt| opt.width()).max().unwrap_or(0);
-                    let descr_ind = indent + max + indent;
-
-                    for opt in *options {
-                        writer.write_all(&SPACES[..indent])?;
-                        opt.write(writer)?;
-                        writer.write_all(&SPACES[..(descr_ind - opt.width() - indent)])?;
-
-                        let arr;
-                        let def = if let Kind::Value {
-                            default: Some(def), ..
-                        } = opt.kind
-                        {
-                            arr = [" [default: ", def, "]\n"];
-                            arr.iter().copied()
-                        } else {
-                            ["\n"].iter().copied()
-                        };
-
-                        for x in Wrap::new(limit - descr_ind, once(opt.descr).chain(def)) {
-                            match x {
-                                crate::dumb_wrap::Item::Part(p) => {
-                                    writer.write_all(p.as_bytes())?
-                                }
-                                crate::dumb_wrap::Item::Break(l) => {
-                                    writer.write_all(l.as_bytes())?;
-                                    writer.write_all(b"\n")?;
-                                    writer.write_all(&SPACES[..(descr_ind)])?;
-                                }
-                            }
-                        }
-                    }
+                if let Some(after_help) = after_help {
+                    out.write_char('\n')?;
+                    parts.markup(out, after_help, 0)?;
+                    out.write_char('\n')?;
                 }
 
                 Ok(())
@@ -140,75 +422,1344 @@ impl Description<'_> {
     }
 }
 
+impl Description<'_> {
+    /// Renders only positional arguments and options which match the `search`, under their
+    /// usual headings.
+    ///
+    /// Hidden options never match, advanced options only match in the [long help](Mode::Long).
+    /// If nothing matches, a line saying so is rendered instead.
+    ///
+    /// ```
+    /// use vvvv::help::{Description, DefaultRenderer, Kind, Opt, Search, Settings};
+    ///
+    /// let descr = Description::DEFAULT
+    ///     .name("fetch")
+    ///     .descr("Fetches things")
+    ///     .usage("fetch [options] <url>")
+    ///     .options(&[
+    ///         Opt {
+    ///             long: Some("proxy"),
+    ///             kind: Kind::Value { name: Some("url"), default: None },
+    ///             descr: "use the proxy",
+    ///             ..Opt::DEFAULT
+    ///         },
+    ///         Opt {
+    ///             long: Some("no-proxy"),
+    ///             descr: "don't use a proxy for the listed hosts",
+    ///             ..Opt::DEFAULT
+    ///         },
+    ///         Opt {
+    ///             long: Some("retries"),
+    ///             descr: "number of retries",
+    ///             ..Opt::DEFAULT
+    ///         },
+    ///     ]);
+    ///
+    /// let mut help = String::new();
+    /// let search = Search::new("PROXY");
+    /// descr
+    ///     .render_search(&search, &DefaultRenderer, &mut help, &Settings::default())
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     help,
+    ///     "Options:\n    --proxy <url>    use the proxy\n    --no-proxy       don't use a proxy for the listed hosts\n",
+    /// );
+    /// ```
+    pub fn render_search(
+        &self,
+        search: &Search<'_>,
+        renderer: &dyn HelpRenderer,
+        out: &mut dyn fmt::Write,
+        settings: &Settings,
+    ) -> fmt::Result {
+        let (sections, positionals, options) = match self {
+            Description::Typed {
+                sections,
+                positionals,
+                options,
+                ..
+            } => (*sections, *positionals, *options),
+            _ => (&[][..], &[][..], &[][..]),
+        };
+
+        let parts = Parts::new(
+            renderer,
+            settings,
+            sections,
+            positionals,
+            options,
+            Some(search),
+        )?;
+        if parts.positionals.is_empty() && parts.options.is_empty() {
+            return writeln!(out, "No options match `{}`.", search.keyword);
+        }
+
+        let mut buf = String::new();
+        parts.body(&mut buf)?;
+        // Sections start with an empty line
+        out.write_str(buf.strip_prefix('\n').unwrap_or(&buf))
+    }
+}
+
+/// Keyword to search for in names and descriptions, see [`Description::render_search`].
+///
+/// Names are matched without their `-`/`--` prefix, i.e. `rbo` matches `--verbose`. Keywords
+/// starting with `--` only match long names and keywords starting with `-` only match short names,
+/// i.e. `--rbo` matches `--verbose`, but `-rbo` doesn't.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Search<'a> {
+    pub keyword: &'a str,
+    /// Fuzzy search matches if all characters of the keyword appear in the text in the same
+    /// order, i.e. `prxy` matches `--proxy`.
+    pub fuzzy: bool,
+}
+
+impl<'a> Search<'a> {
+    /// Exact (but case-insensitive) search for the `keyword`.
+    pub fn new(keyword: &'a str) -> Self {
+        Self {
+            keyword,
+            fuzzy: false,
+        }
+    }
+
+    /// Returns `true` if `text` contains the keyword, ignoring case.
+    pub fn matches(&self, text: &str) -> bool {
+        let keyword = self.keyword.to_lowercase();
+        let text = text.to_lowercase();
+
+        if !self.fuzzy {
+            return text.contains(&keyword);
+        }
+
+        let mut chars = text.chars();
+        keyword.chars().all(|k| chars.any(|c| c == k))
+    }
+
+    fn matches_pos(&self, pos: &Pos<'_>) -> bool {
+        let texts = [pos.name, pos.descr, pos.long_descr.unwrap_or("")];
+        texts.iter().any(|text| self.matches(text))
+    }
+
+    fn matches_opt(&self, opt: &Opt<'_>) -> bool {
+        let mut names = opt
+            .short
+            .map(Name::Short)
+            .into_iter()
+            .chain(opt.long.map(Name::Long))
+            .chain(opt.aliases.iter().map(|alias| alias.name));
+        let texts = [opt.descr, opt.long_descr.unwrap_or("")];
+
+        names.any(|name| self.matches_name(name)) || texts.iter().any(|text| self.matches(text))
+    }
+
+    /// Keywords starting with `--` only match long names and keywords starting with `-` only
+    /// match short names, other keywords match names without their `-`/`--` prefix.
+    fn matches_name(&self, name: Name<'_>) -> bool {
+        let (keyword, short, long) = match self.keyword.strip_prefix('-') {
+            Some(keyword) => match keyword.strip_prefix('-') {
+                Some(keyword) => (keyword, false, true),
+                None => (keyword, true, false),
+            },
+            None => (self.keyword, true, true),
+        };
+        let search = Search { keyword, ..*self };
+
+        match name {
+            Name::Short(name) => short && search.matches(name.encode_utf8(&mut [0; 4])),
+            Name::Long(name) => long && search.matches(name),
+        }
+    }
+}
+
+/// Help requested on the command line with `-h` or `--help`, optionally followed by a keyword
+/// to [search](Description::render_search) for.
+///
+/// ```no_run
+/// use vvvv::{help::{Description, HelpRequest}, Token};
+///
+/// const DESCRIPTION: Description<'static> = Description::Raw("Usage: cmd [--proxy <url>]\n");
+///
+/// let args: Vec<_> = std::env::args().skip(1).collect();
+/// for token in Token::parse(args.iter().map(String::as_str)) {
+///     if let Some(request) = HelpRequest::from_token(&token) {
+///         request.print(&DESCRIPTION).unwrap();
+///         std::process::exit(0);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HelpRequest<'a> {
+    pub mode: Mode,
+    pub keyword: Option<&'a str>,
+}
+
+impl<'a> HelpRequest<'a> {
+    /// Returns the request if `token` is `-h` or `--help`, the value of the token (`--help proxy`,
+    /// or `--help=proxy` with [`ParseConfig::split_eq`](crate::ParseConfig::split_eq)) is the
+    /// keyword.
+    ///
+    /// ```
+    /// use vvvv::{help::{HelpRequest, Mode}, Token};
+    ///
+    /// let token = Token::Long { key: "help", value: Some("proxy") };
+    /// assert_eq!(
+    ///     HelpRequest::from_token(&token),
+    ///     Some(HelpRequest { mode: Mode::Long, keyword: Some("proxy") }),
+    /// );
+    /// ```
+    pub fn from_token(token: &Token<'a>) -> Option<Self> {
+        let mode = Mode::from_token(token)?;
+        let keyword = match *token {
+            Token::Short { value, .. } | Token::Long { value, .. } => value,
+            _ => None,
+        };
+
+        Some(Self { mode, keyword })
+    }
+
+    /// Renders the requested help, the full help or only options matching the keyword.
+    pub fn render(
+        &self,
+        descr: &Description<'_>,
+        out: &mut dyn fmt::Write,
+        settings: &Settings,
+    ) -> fmt::Result {
+        let settings = Settings {
+            mode: self.mode,
+            ..*settings
+        };
+
+        match self.keyword {
+            Some(keyword) => {
+                let search = Search::new(keyword);
+                descr.render_search(&search, &DefaultRenderer, out, &settings)
+            }
+            None => descr.render(out, &settings),
+        }
+    }
+
+    /// Prints the requested help to the stdout with [automatic](Settings::auto) settings.
+    pub fn print(&self, descr: &Description<'_>) -> io::Result<()> {
+        let mut buf = String::new();
+        self.render(descr, &mut buf, &Settings::auto(Stream::Stdout))
+            .expect("writing to a `String` can't fail");
+        io::stdout().lock().write_all(buf.as_bytes())
+    }
+}
+
+/// Renders the help without wrapping, or wrapping lines at the width of the formatter (i.e.
+/// `format!("{:80}", descr)`).
+impl Display for Description<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let settings = Settings {
+            width_limit: f.width(),
+            ..Settings::default()
+        };
+
+        self.render(f, &settings)
+    }
+}
+
+/// Renders headings and rows of a [`Description::Typed`] help, see
+/// [`Description::render_with`].
+///
+/// Every method has a default implementation which produces the default layout, so a renderer
+/// only overrides the parts it changes:
+///
+/// ```
+/// use std::fmt;
+///
+/// use vvvv::help::{Description, HelpRenderer, Kind, Opt, Settings};
+///
+/// /// Writes `--xor, -x <VAL>` instead of `-x, --xor <val>`.
+/// struct LongFirst;
+///
+/// impl HelpRenderer for LongFirst {
+///     fn option_name(
+///         &self,
+///         out: &mut dyn fmt::Write,
+///         _settings: &Settings,
+///         opt: &Opt<'_>,
+///     ) -> fmt::Result {
+///         let names = opt.long.map(|l| format!("--{}", l));
+///         let names = names.into_iter().chain(opt.short.map(|s| format!("-{}", s)));
+///         out.write_str(&names.collect::<Vec<_>>().join(", "))?;
+///
+///         if let Kind::Value { name, .. } = opt.kind {
+///             write!(out, " <{}>", name.unwrap_or("val").to_uppercase())?;
+///         }
+///
+///         Ok(())
+///     }
+/// }
+///
+/// let descr = Description::DEFAULT
+///     .name("xor")
+///     .descr("Xors things")
+///     .usage("xor [--xor <val>]")
+///     .options(&[Opt {
+///         short: Some('x'),
+///         long: Some("xor"),
+///         kind: Kind::Value { name: None, default: None },
+///         descr: "value to xor with",
+///         ..Opt::DEFAULT
+///     }]);
+///
+/// let mut help = String::new();
+/// descr.render_with(&LongFirst, &mut help, &Settings::default()).unwrap();
+/// assert!(help.contains("    --xor, -x <VAL>    value to xor with\n"));
+/// ```
+pub trait HelpRenderer {
+    /// Writes a heading, i.e. `Options:`.
+    fn heading(
+        &self,
+        out: &mut dyn fmt::Write,
+        settings: &Settings,
+        heading: Heading<'_>,
+    ) -> fmt::Result {
+        let (on, off) = settings.paint(settings.theme.heading);
+        write!(out, "{}{}:{}", on, heading, off)
+    }
+
+    /// Writes a section: an empty line, the heading and the rows.
+    fn section(
+        &self,
+        out: &mut dyn fmt::Write,
+        columns: &Columns<'_>,
+        heading: Heading<'_>,
+        positionals: &[Pos<'_>],
+        options: &[Opt<'_>],
+    ) -> fmt::Result {
+        out.write_char('\n')?;
+        self.heading(out, columns.settings(), heading)?;
+        out.write_char('\n')?;
+
+        for pos in positionals {
+            self.positional_row(out, columns, pos)?;
+        }
+
+        for opt in options {
+            self.option_row(out, columns, opt)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the name of a positional argument, the width of the name column is measured from
+    /// the names written by this method.
+    fn positional_name(
+        &self,
+        out: &mut dyn fmt::Write,
+        settings: &Settings,
+        pos: &Pos<'_>,
+    ) -> fmt::Result {
+        let (on, off) = settings.paint(settings.theme.option);
+        write!(out, "{}{}{}", on, pos.name, off)
+    }
+
+    /// Writes names of an option and its value placeholder, i.e. `-o, --output <file>`.
+    ///
+    /// The width of the name column is measured from the names written by this method.
+    fn option_name(
+        &self,
+        out: &mut dyn fmt::Write,
+        settings: &Settings,
+        opt: &Opt<'_>,
+    ) -> fmt::Result {
+        opt.write(out, settings)
+    }
+
+    /// Writes a row of a positional argument, ending with a newline.
+    fn positional_row(
+        &self,
+        out: &mut dyn fmt::Write,
+        columns: &Columns<'_>,
+        pos: &Pos<'_>,
+    ) -> fmt::Result {
+        let settings = columns.settings();
+        let mut name = String::new();
+        self.positional_name(&mut name, settings, pos)?;
+
+        let descr = settings.mode.pick(pos.descr, pos.long_descr);
+        columns.row(out, &name, descr, &[])
+    }
+
+    /// Writes a row of an option, ending with a newline.
+    ///
+    /// The description is followed by the default value, deprecation and visible aliases.
+    fn option_row(
+        &self,
+        out: &mut dyn fmt::Write,
+        columns: &Columns<'_>,
+        opt: &Opt<'_>,
+    ) -> fmt::Result {
+        let settings = columns.settings();
+        let mut name = String::new();
+        self.option_name(&mut name, settings, opt)?;
+
+        let descr = settings.mode.pick(opt.descr, opt.long_descr);
+        let mut notes = Vec::new();
+        if let Kind::Value {
+            default: Some(def), ..
+        } = opt.kind
+        {
+            let (on, off) = settings.paint(settings.theme.default);
+            notes.extend(&[" [default: ", on, def, off, "]"]);
+        }
+        match (opt.deprecated, settings.mode) {
+            (None, _) => {}
+            (
+                Some(Deprecated {
+                    replacement: Some(r),
+                }),
+                Mode::Long,
+            ) => notes.extend(&[" [deprecated, use `", r, "` instead]"]),
+            (Some(_), _) => notes.push(" [deprecated]"),
+        }
+        let mut aliases = String::new();
+        let mut visible = opt.aliases.iter().filter(|alias| alias.visible);
+        if let (true, Some(first)) = (settings.show_aliases, visible.next()) {
+            write!(aliases, " [aliases: {}", first.name)?;
+            for alias in visible {
+                write!(aliases, ", {}", alias.name)?;
+            }
+            aliases.push(']');
+            notes.push(&aliases);
+        }
+
+        columns.row(out, &name, descr, &notes)
+    }
+
+    /// Writes an example, ending with a newline.
+    ///
+    /// The explanation is written below the command.
+    fn example_row(
+        &self,
+        out: &mut dyn fmt::Write,
+        columns: &Columns<'_>,
+        example: &Example<'_>,
+    ) -> fmt::Result {
+        let settings = columns.settings();
+        let (on, off) = settings.paint(settings.theme.option);
+        let command = format!("{}{}{}", on, example.command, off);
+        columns.row(out, &command, example.descr, &[])
+    }
+
+    /// Writes a row of an environment variable, ending with a newline.
+    ///
+    /// Environment variables have their own columns, which are measured from their names.
+    fn env_row(
+        &self,
+        out: &mut dyn fmt::Write,
+        columns: &Columns<'_>,
+        env: &Env<'_>,
+    ) -> fmt::Result {
+        let settings = columns.settings();
+        let (on, off) = settings.paint(settings.theme.option);
+        let name = format!("{}{}{}", on, env.name, off);
+
+        let mut notes = Vec::new();
+        if let Some(def) = env.default {
+            let (on, off) = settings.paint(settings.theme.default);
+            notes.extend(&[" [default: ", on, def, off, "]"]);
+        }
+
+        columns.row(out, &name, env.descr, &notes)
+    }
+}
+
+/// Renderer of the default layout.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultRenderer;
+
+impl HelpRenderer for DefaultRenderer {}
+
+/// Heading of a section, see [`HelpRenderer::heading`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heading<'a> {
+    /// `Usage`
+    Usage,
+    /// `Positional arguments`
+    Positionals,
+    /// `Options`
+    Options,
+    /// Named section, see [`Opt::section`].
+    Section(&'a str),
+    /// `Examples`
+    Examples,
+    /// `Environment variables`
+    Environment,
+}
+
+impl Display for Heading<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Heading::Usage => "Usage",
+            Heading::Positionals => "Positional arguments",
+            Heading::Options => "Options",
+            Heading::Section(name) => name,
+            Heading::Examples => "Examples",
+            Heading::Environment => "Environment variables",
+        })
+    }
+}
+
+/// Column layout of rows, names are written in the first column and descriptions in the second
+/// one, see [`HelpRenderer::option_row`].
+pub struct Columns<'s> {
+    settings: &'s Settings,
+    layout: Layout,
+}
+
+impl Columns<'_> {
+    /// Returns settings the help is rendered with.
+    pub fn settings(&self) -> &Settings {
+        self.settings
+    }
+
+    /// Writes a row with `name` in the first column and `descr` wrapped in the second one.
+    ///
+    /// `descr` may contain [markup](Opt::descr), it is followed by `notes` (i.e. the default
+    /// value) which are written as is. The row ends with a newline.
+    pub fn row(
+        &self,
+        out: &mut dyn fmt::Write,
+        name: &str,
+        descr: &str,
+        notes: &[&str],
+    ) -> fmt::Result {
+        let name_fn = |out: &mut dyn fmt::Write| out.write_str(name);
+
+        let mut wrap = self.layout.row(out, wrap::width(name), &name_fn)?;
+        markup::push(&mut wrap, descr, self.settings)?;
+        for chunk in notes {
+            wrap.push(chunk)?;
+        }
+        wrap.push("\n")?;
+        wrap.finish()
+    }
+}
+
+/// Parts of a [`Description::Typed`] help which are rendered either in the default layout or
+/// into a template.
+struct Parts<'d, 'a> {
+    renderer: &'d dyn HelpRenderer,
+    columns: Columns<'d>,
+    sections: &'d [&'a str],
+    /// Positional arguments which match the search, if any.
+    positionals: Vec<Pos<'a>>,
+    /// Options which should be rendered in the current [`Mode`] and match the search, if any.
+    options: Vec<Opt<'a>>,
+    examples: &'d [Example<'a>],
+    example_columns: Columns<'d>,
+    env: &'d [Env<'a>],
+    env_columns: Columns<'d>,
+}
+
+impl<'d, 'a> Parts<'d, 'a> {
+    fn new(
+        renderer: &'d dyn HelpRenderer,
+        settings: &'d Settings,
+        sections: &'d [&'a str],
+        positionals: &[Pos<'a>],
+        options: &[Opt<'a>],
+        search: Option<&Search<'_>>,
+    ) -> Result<Self, fmt::Error> {
+        let positionals: Vec<_> = positionals
+            .iter()
+            .filter(|pos| search.is_none_or(|s| s.matches_pos(pos)))
+            .copied()
+            .collect();
+        let options: Vec<_> = options
+            .iter()
+            .filter(|opt| !opt.hidden)
+            .filter(|opt| settings.mode == Mode::Long || !opt.advanced)
+            .filter(|opt| search.is_none_or(|s| s.matches_opt(opt)))
+            .copied()
+            .collect();
+
+        let mut names = Vec::with_capacity(positionals.len() + options.len());
+        for pos in &positionals {
+            let mut name = String::new();
+            renderer.positional_name(&mut name, settings, pos)?;
+            names.push(wrap::width(&name));
+        }
+        for opt in &options {
+            let mut name = String::new();
+            renderer.option_name(&mut name, settings, opt)?;
+            names.push(wrap::width(&name));
+        }
+        let layout = Layout::new(settings, names.into_iter());
+
+        Ok(Self {
+            renderer,
+            columns: Columns { settings, layout },
+            sections,
+            positionals,
+            options,
+            examples: &[],
+            example_columns: Columns {
+                settings,
+                layout: Layout::stacked(settings),
+            },
+            env: &[],
+            env_columns: Columns {
+                settings,
+                layout: Layout::stacked(settings),
+            },
+        })
+    }
+
+    /// Adds examples and environment variables.
+    fn extra(mut self, examples: &'d [Example<'a>], env: &'d [Env<'a>]) -> Self {
+        let settings = self.columns.settings;
+        let names = env.iter().map(|env| wrap::width(env.name));
+
+        self.examples = examples;
+        self.env = env;
+        self.env_columns.layout = Layout::new(settings, names);
+        self
+    }
+
+    /// Writes wrapped `text` which starts at the column `start`, following lines are indented
+    /// to the same column.
+    fn text(&self, out: &mut dyn fmt::Write, text: &str, start: usize) -> fmt::Result {
+        let limit = self.columns.settings.width_limit.unwrap_or(usize::MAX);
+        let mut wrap = Wrap::new(out, limit, start, start);
+        wrap.push(text)?;
+        wrap.finish()
+    }
+
+    /// Same as [`Parts::text`], but interprets [markup](Opt::descr).
+    fn markup(&self, out: &mut dyn fmt::Write, text: &str, start: usize) -> fmt::Result {
+        let limit = self.columns.settings.width_limit.unwrap_or(usize::MAX);
+        let mut wrap = Wrap::new(out, limit, start, start);
+        markup::push(&mut wrap, text, self.columns.settings)?;
+        wrap.finish()
+    }
+
+    /// Writes positional arguments and options under their headings.
+    fn body(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        let positionals: Vec<_> = self
+            .positionals
+            .iter()
+            .filter(|pos| pos.section.is_none())
+            .copied()
+            .collect();
+        let options: Vec<_> = self
+            .options
+            .iter()
+            .filter(|opt| opt.section.is_none())
+            .copied()
+            .collect();
+
+        if !positionals.is_empty() {
+            let heading = Heading::Positionals;
+            self.renderer
+                .section(out, &self.columns, heading, &positionals, &[])?;
+        }
+
+        if !options.is_empty() {
+            let heading = Heading::Options;
+            self.renderer
+                .section(out, &self.columns, heading, &[], &options)?;
+        }
+
+        self.sections(out)
+    }
+
+    /// Writes rows of positional arguments without a section.
+    fn positionals(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        for pos in self.positionals.iter().filter(|pos| pos.section.is_none()) {
+            self.renderer.positional_row(out, &self.columns, pos)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes rows of options without a section.
+    fn options(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        for opt in self.options.iter().filter(|opt| opt.section.is_none()) {
+            self.renderer.option_row(out, &self.columns, opt)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes rows of examples.
+    fn examples(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        for example in self.examples {
+            self.renderer
+                .example_row(out, &self.example_columns, example)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes rows of environment variables.
+    fn env(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        for env in self.env {
+            self.renderer.env_row(out, &self.env_columns, env)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes named sections with their headings.
+    fn sections(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        for section in section_order(self.sections, &self.positionals, &self.options) {
+            let positionals: Vec<_> = self
+                .positionals
+                .iter()
+                .filter(|p| p.section == Some(section))
+                .copied()
+                .collect();
+            let options: Vec<_> = self
+                .options
+                .iter()
+                .filter(|o| o.section == Some(section))
+                .copied()
+                .collect();
+
+            let heading = Heading::Section(section);
+            self.renderer
+                .section(out, &self.columns, heading, &positionals, &options)?;
+        }
+
+        Ok(())
+    }
+
+    /// Renders `template`, see [`Description::Typed::template`].
+    fn template(
+        &self,
+        out: &mut Column<'_>,
+        template: &str,
+        name: &str,
+        descr: &str,
+        usage: &str,
+        after_help: Option<&str>,
+    ) -> fmt::Result {
+        let mut rest = template;
+
+        while let Some(i) = rest.find(['{', '}']) {
+            out.write_str(&rest[..i])?;
+            rest = &rest[i..];
+
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                out.write_str(&rest[..1])?;
+                rest = &rest[2..];
+                continue;
+            }
+
+            let placeholder = rest[1..]
+                .find(['{', '}'])
+                .filter(|&end| rest[1 + end..].starts_with('}'))
+                .map(|end| &rest[1..1 + end]);
+            let placeholder = match (rest.starts_with('{'), placeholder) {
+                (true, Some(placeholder)) => placeholder,
+                _ => {
+                    // Unmatched brace
+                    out.write_str(&rest[..1])?;
+                    rest = &rest[1..];
+                    continue;
+                }
+            };
+
+            let col = out.col;
+            match placeholder {
+                "name" => out.write_str(name)?,
+                "descr" => self.markup(out, descr, col)?,
+                "usage" => self.text(out, usage, col)?,
+                "positionals" => self.positionals(out)?,
+                "options" => self.options(out)?,
+                "sections" => self.sections(out)?,
+                "examples" => self.examples(out)?,
+                "env" => self.env(out)?,
+                "after-help" => self.markup(out, after_help.unwrap_or(""), col)?,
+                _ => write!(out, "{{{}}}", placeholder)?,
+            }
+            rest = &rest[placeholder.len() + 2..];
+        }
+
+        out.write_str(rest)
+    }
+}
+
+/// Writer which tracks the column of the last written character, used to wrap placeholders of
+/// templates.
+struct Column<'o> {
+    out: &'o mut dyn fmt::Write,
+    col: usize,
+}
+
+impl fmt::Write for Column<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match s.rfind('\n') {
+            Some(i) => self.col = wrap::width(&s[i + 1..]),
+            None => self.col += wrap::width(s),
+        }
+
+        self.out.write_str(s)
+    }
+}
+
+/// Returns names of sections in the order they should be rendered.
+///
+/// Declared sections which are not used by any option or positional argument are skipped, used
+/// sections which are not declared are appended in order of their first use.
+pub(crate) fn section_order<'a>(
+    declared: &[&'a str],
+    positionals: &[Pos<'a>],
+    options: &[Opt<'a>],
+) -> Vec<&'a str> {
+    let used: Vec<_> = positionals
+        .iter()
+        .filter_map(|pos| pos.section)
+        .chain(options.iter().filter_map(|opt| opt.section))
+        .collect();
+
+    let mut order: Vec<_> = declared
+        .iter()
+        .copied()
+        .filter(|s| used.contains(s))
+        .collect();
+    for section in used {
+        if !order.contains(&section) {
+            order.push(section);
+        }
+    }
+
+    order
+}
+
+/// Minimal width of the description column, if there is not enough space the descriptions are
+/// written below the names.
+const MIN_DESCR_WIDTH: usize = 20;
+
+/// Column layout of positional argument and option rows.
+///
+/// Names are written in the first column and descriptions in the second one. Names wider than
+/// [`Settings::max_name_width`] are written on their own line with the description below.
+struct Layout {
+    limit: usize,
+    indent: usize,
+    /// Width of the name column.
+    names: usize,
+    /// Column at which descriptions start.
+    descr: usize,
+}
+
+impl Layout {
+    fn new(settings: &Settings, names: impl Iterator<Item = usize>) -> Self {
+        let limit = settings.width_limit.unwrap_or(usize::MAX);
+        let indent = settings.indent;
+
+        let names = names
+            .filter(|&w| w <= settings.max_name_width)
+            .max()
+            .unwrap_or(0);
+        let descr = indent + names + indent;
+
+        if descr.saturating_add(MIN_DESCR_WIDTH) > limit {
+            // Not enough space for two columns, write all descriptions below names
+            return Self::stacked(settings);
+        }
+
+        Self {
+            limit,
+            indent,
+            names,
+            descr,
+        }
+    }
+
+    /// Layout which writes all descriptions below names.
+    fn stacked(settings: &Settings) -> Self {
+        Self {
+            limit: settings.width_limit.unwrap_or(usize::MAX),
+            indent: settings.indent,
+            names: 0,
+            descr: settings.indent * 2,
+        }
+    }
+
+    /// Writes the name of a row and returns wrapper for its description.
+    fn row<'o>(
+        &self,
+        out: &'o mut dyn fmt::Write,
+        name_width: usize,
+        name: &dyn Fn(&mut dyn fmt::Write) -> fmt::Result,
+    ) -> Result<Wrap<'o>, fmt::Error> {
+        write!(out, "{:1$}", "", self.indent)?;
+        name(out)?;
+
+        if name_width <= self.names {
+            let start = self.indent + name_width;
+            Ok(Wrap::new(out, self.limit, start, self.descr).pad(self.descr - start))
+        } else {
+            let mut wrap = Wrap::new(out, self.limit, self.indent + name_width, self.descr);
+            wrap.push("\n")?;
+            Ok(wrap)
+        }
+    }
+}
+
+/// Settings used to render a [`Description`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    /// Maximum width of a line, `None` means that lines are not wrapped.
+    pub width_limit: Option<usize>,
+    /// Indentation of options and positional arguments, also used as a gap between their names
+    /// and descriptions.
+    pub indent: usize,
+    /// Maximum width of the name column.
+    ///
+    /// Names of options and positional arguments which are wider than this are written on their
+    /// own line with the description below, so a single long name doesn't push all descriptions
+    /// to the right.
+    pub max_name_width: usize,
+    /// Whether to list [visible aliases](Alias::visible) of options.
+    pub show_aliases: bool,
+    /// Whether to render the short or the long help.
+    pub mode: Mode,
+    /// Whether to style the help with ANSI escape sequences, see [`ColorChoice::resolve`].
+    pub styled: bool,
+    /// Styles used if `styled` is `true`.
+    pub theme: Theme,
+}
+
+impl Settings {
+    /// Returns settings suitable for printing to the `stream`.
+    ///
+    /// The width limit is detected with [`auto_width`], styles are enabled according to
+    /// [`ColorChoice::Auto`].
+    pub fn auto(stream: Stream) -> Self {
+        Self {
+            width_limit: auto_width(stream),
+            styled: ColorChoice::Auto.resolve(stream),
+            ..Self::default()
+        }
+    }
+
+    /// Returns escape sequences which start and end `style`, if styles are enabled.
+    pub fn paint(&self, style: &'static str) -> (&'static str, &'static str) {
+        if self.styled {
+            (style, "\x1b[0m")
+        } else {
+            ("", "")
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            width_limit: None,
+            indent: 4,
+            max_name_width: 30,
+            show_aliases: true,
+            mode: Mode::Long,
+            styled: false,
+            theme: Theme::DEFAULT,
+        }
+    }
+}
+
+/// Kind of the help to render, like `-h` and `--help` in git and cargo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Compact help, one line per option.
+    ///
+    /// Only the first line of descriptions is rendered, [advanced](Opt::advanced) options are
+    /// skipped.
+    Short,
+    /// Full help with long descriptions and advanced options.
+    Long,
+}
+
+impl Mode {
+    /// Returns the mode requested by `-h` (short) or `--help` (long).
+    ///
+    /// ```
+    /// use vvvv::{help::Mode, Token};
+    ///
+    /// let token = Token::Long { key: "help", value: None };
+    /// assert_eq!(Mode::from_token(&token), Some(Mode::Long));
+    /// ```
+    pub fn from_token(token: &Token<'_>) -> Option<Self> {
+        match token {
+            Token::Short { key: 'h', .. } => Some(Self::Short),
+            Token::Long { key: "help", .. } => Some(Self::Long),
+            _ => None,
+        }
+    }
+
+    /// Returns description which should be rendered in this mode.
+    fn pick<'a>(self, descr: &'a str, long_descr: Option<&'a str>) -> &'a str {
+        match self {
+            Self::Short => descr.lines().next().unwrap_or(""),
+            Self::Long => long_descr.unwrap_or(descr),
+        }
+    }
+}
+
+/// Styles used to render help, see [`Settings::styled`].
+///
+/// Every style is an ANSI escape sequence, i.e. `"\x1b[1m"` for bold text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Style of section headings, i.e. `Options:`.
+    pub heading: &'static str,
+    /// Style of option names and positional argument names.
+    pub option: &'static str,
+    /// Style of value placeholders, i.e. `<file>`.
+    pub placeholder: &'static str,
+    /// Style of default values.
+    pub default: &'static str,
+    /// Style of `` `code` `` in descriptions.
+    pub code: &'static str,
+    /// Style of `*emphasis*` in descriptions.
+    pub emphasis: &'static str,
+}
+
+impl Theme {
+    /// Bold underlined headings, bold option names and code, italic placeholders and emphasis,
+    /// dimmed defaults.
+    pub const DEFAULT: Self = Self {
+        heading: "\x1b[1;4m",
+        option: "\x1b[1m",
+        placeholder: "\x1b[3m",
+        default: "\x1b[2m",
+        code: "\x1b[1m",
+        emphasis: "\x1b[3m",
+    };
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Maximum line width used by [`auto_width`], longer lines are hard to read.
+pub const MAX_WIDTH: usize = 100;
+
+/// Detects the width limit for help printed to the `stream`.
+///
+/// Uses `$COLUMNS` if it is set, otherwise queries the size of the terminal the `stream` is
+/// connected to. Returns `None` (i.e. no wrapping) if the `stream` is not a terminal.
+///
+/// The result is clamped to [`MAX_WIDTH`].
+pub fn auto_width(stream: Stream) -> Option<usize> {
+    resolve_width(env::var("COLUMNS").ok().as_deref(), || term::width(stream))
+}
+
+fn resolve_width(columns: Option<&str>, term: impl FnOnce() -> Option<usize>) -> Option<usize> {
+    columns
+        .and_then(|c| c.trim().parse().ok())
+        .filter(|&c| c > 0)
+        .or_else(term)
+        .map(|w: usize| w.min(MAX_WIDTH))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Pos<'a> {
     pub name: &'a str,
     pub descr: &'a str,
+    /// Description used in the [long help](Mode::Long) instead of `descr`.
+    pub long_descr: Option<&'a str>,
+    /// Section under which this positional argument is rendered, see [`Opt::section`].
+    pub section: Option<&'a str>,
+    /// Whether this positional argument must be given, adding a required positional argument
+    /// is a [breaking change](crate::compat::Change::PositionalAdded).
+    pub required: Required<'a>,
+}
+
+impl<'a> Pos<'a> {
+    /// Optional positional argument without name and description, useful with the struct update syntax:
+    ///
+    /// ```
+    /// use vvvv::help::Pos;
+    ///
+    /// const FILE: Pos = Pos {
+    ///     name: "file",
+    ///     descr: "file to process",
+    ///     ..Pos::DEFAULT
+    /// };
+    /// ```
+    pub const DEFAULT: Self = Self {
+        name: "",
+        descr: "",
+        long_descr: None,
+        section: None,
+        required: Required::Optional,
+    };
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Opt<'a> {
     pub short: Option<char>,
     pub long: Option<&'a str>,
     pub kind: Kind<'a>,
+    /// Description of the option.
+    ///
+    /// Descriptions (and `descr` and `after_help` of [`Description::Typed`]) support a small
+    /// subset of Markdown:
+    ///
+    /// - `\n` starts a new line and an empty line separates paragraphs,
+    /// - lines starting with `- ` are bullets, their wrapped lines are indented under the text,
+    /// - `` `code` `` and `*emphasis*` are rendered with [`Theme::code`] and
+    ///   [`Theme::emphasis`]. If styles are disabled, backticks are kept and asterisks are
+    ///   removed.
+    ///
+    /// [Man pages](crate::man) and [Markdown](crate::markdown) interpret the same markup, with
+    /// their own paragraphs, lists, code and emphasis.
     pub descr: &'a str,
+    /// Description used in the [long help](Mode::Long) instead of `descr`.
+    pub long_descr: Option<&'a str>,
     pub required: Required<'a>,
+    /// Advanced options are only rendered in the [long help](Mode::Long).
+    pub advanced: bool,
+    /// Additional names of this option.
+    pub aliases: &'a [Alias<'a>],
+    /// Hidden options are not rendered in help, but are still parsed.
+    pub hidden: bool,
+    /// Deprecated options are marked in help, using them produces a
+    /// [warning](crate::Warning::Deprecated).
+    pub deprecated: Option<Deprecated<'a>>,
+    /// Section under which this option is rendered.
+    ///
+    /// Options without a section are rendered under `Options:`, options with a section are
+    /// rendered under a heading with the section name. The order of sections is defined by
+    /// `sections` of [`Description::Typed`].
+    pub section: Option<&'a str>,
 }
 
-impl Opt<'_> {
-    fn width(&self) -> usize {
-        matches!(self.kind, Kind::Value { .. }) as usize * 6
-            + match (self.short, self.long) {
-                (None, None) => 0,
-                (None, Some(l)) => 2 + l.width(),
-                (Some(s), None) => 1 + s.width().unwrap_or(0),
-                (Some(s), Some(l)) => 5 + s.width().unwrap_or(0) + l.width(),
+impl<'a> Opt<'a> {
+    /// Optional unnamed flag without description, useful with the struct update syntax:
+    ///
+    /// ```
+    /// use vvvv::help::{Kind, Opt, Required};
+    ///
+    /// const VERBOSE: Opt = Opt {
+    ///     short: Some('v'),
+    ///     long: Some("verbose"),
+    ///     kind: Kind::Count,
+    ///     descr: "verbosity level",
+    ///     ..Opt::DEFAULT
+    /// };
+    /// ```
+    pub const DEFAULT: Self = Self {
+        short: None,
+        long: None,
+        kind: Kind::Flag,
+        descr: "",
+        long_descr: None,
+        required: Required::Optional,
+        advanced: false,
+        aliases: &[],
+        hidden: false,
+        deprecated: None,
+        section: None,
+    };
+}
+
+impl<'a> Opt<'a> {
+    /// Returns `true` if `token` refers to this option by its short or long name or by any of
+    /// its aliases.
+    ///
+    /// [Keyed](Token::Keyed) tokens match by name regardless of their prefix, a single character
+    /// key also matches the short name.
+    ///
+    /// ```
+    /// use vvvv::{
+    ///     help::{Alias, Name, Opt},
+    ///     Prefix, Token,
+    /// };
+    ///
+    /// let opt = Opt {
+    ///     short: Some('n'),
+    ///     long: Some("dry-run"),
+    ///     aliases: &[Alias {
+    ///         name: Name::Long("dryrun"),
+    ///         visible: false,
+    ///     }],
+    ///     ..Opt::DEFAULT
+    /// };
+    ///
+    /// assert!(opt.matches(&Token::Short { key: 'n', value: None }));
+    /// assert!(opt.matches(&Token::Long { key: "dryrun", value: None }));
+    /// assert!(!opt.matches(&Token::Long { key: "dry", value: None }));
+    /// assert!(opt.matches(&Token::Keyed { prefix: Prefix::Plus, key: "n", value: None }));
+    /// ```
+    pub fn matches(&self, token: &Token<'_>) -> bool {
+        let name = match *token {
+            Token::Short { key, .. } => Name::Short(key),
+            Token::Long { key, .. } => Name::Long(key),
+            // `+x`, `-name` or `/name` match the option with such short or long name
+            Token::Keyed { key, .. } => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(short), None) if self.has_name(Name::Short(short)) => return true,
+                    _ => Name::Long(key),
+                }
+            }
+            _ => return false,
+        };
+
+        self.has_name(name)
+    }
+
+    /// Returns the long name and long aliases of this option.
+    pub(crate) fn long_names(&self) -> impl Iterator<Item = &'a str> + '_ {
+        let aliases = self.aliases.iter().filter_map(|alias| match alias.name {
+            Name::Long(name) => Some(name),
+            Name::Short(_) => None,
+        });
+        self.long.into_iter().chain(aliases)
+    }
+
+    /// Returns `true` if `name` is the short or long name of this option or any of its aliases.
+    pub(crate) fn has_name(&self, name: Name<'_>) -> bool {
+        let primary = match name {
+            Name::Short(key) => self.short == Some(key),
+            Name::Long(key) => self.long == Some(key),
+        };
+
+        primary || self.aliases.iter().any(|alias| alias.name == name)
+    }
+
+    /// Returns the name of this option in a usage line, i.e. `-o <file>` or `-v...`. The short
+    /// name is preferred.
+    fn usage_name(&self) -> Option<String> {
+        let mut name = match (self.short, self.long) {
+            (Some(short), _) => Name::Short(short).to_string(),
+            (None, Some(long)) => Name::Long(long).to_string(),
+            (None, None) => return None,
+        };
+
+        match self.kind {
+            Kind::Value { name: value, .. } => {
+                name = format!("{} <{}>", name, value.unwrap_or("val"))
             }
+            Kind::Count => name.push_str("..."),
+            Kind::Flag => {}
+        }
+
+        Some(name)
+    }
+
+    /// Returns the long description followed by the default value, deprecation and visible
+    /// aliases, like in the [long help](Mode::Long). Used by man pages and Markdown.
+    pub(crate) fn long_text(&self) -> String {
+        let mut text = self.long_descr.unwrap_or(self.descr).to_owned();
+        if let Kind::Value {
+            default: Some(def), ..
+        } = self.kind
+        {
+            text.push_str(&format!(" [default: {}]", def));
+        }
+        match self.deprecated {
+            Some(Deprecated {
+                replacement: Some(r),
+            }) => text.push_str(&format!(" [deprecated, use `{}` instead]", r)),
+            Some(_) => text.push_str(" [deprecated]"),
+            None => {}
+        }
+
+        let visible: Vec<_> = self
+            .aliases
+            .iter()
+            .filter(|alias| alias.visible)
+            .map(|alias| alias.name.to_string())
+            .collect();
+        if !visible.is_empty() {
+            text.push_str(&format!(" [aliases: {}]", visible.join(", ")));
+        }
+
+        text
     }
 
-    fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+    fn write(&self, out: &mut dyn fmt::Write, settings: &Settings) -> fmt::Result {
+        let (on, off) = settings.paint(settings.theme.option);
         match (self.short, self.long) {
             (None, None) => Ok(()),
-            (None, Some(l)) => writer
-                .write_all(b"--")
-                .and_then(|()| writer.write_all(l.as_bytes())),
-            (Some(s), None) => {
-                let mut buf = [0; 4];
-                let s = s.encode_utf8(&mut buf);
-                writer
-                    .write_all(b"-")
-                    .and_then(|()| writer.write_all(s.as_bytes()))
-            }
-            (Some(s), Some(l)) => {
-                let mut buf = [0; 4];
-                let s = s.encode_utf8(&mut buf);
-                writer
-                    .write_all(b"-")
-                    .and_then(|()| writer.write_all(s.as_bytes()))
-                    .and_then(|()| writer.write_all(b", --"))
-                    .and_then(|()| writer.write_all(l.as_bytes()))
-            }
+            (None, Some(l)) => write!(out, "{}--{}{}", on, l, off),
+            (Some(s), None) => write!(out, "{}-{}{}", on, s, off),
+            (Some(s), Some(l)) => write!(out, "{}-{}{}, {}--{}{}", on, s, off, on, l, off),
         }?;
 
         if let Kind::Value { name, .. } = &self.kind {
-            match name {
-                Some(name) => {
-                    writer.write_all(b" <")?;
-                    writer.write_all(name.as_bytes())?;
-                    writer.write_all(b">")?;
-                }
-                None => writer.write_all(b" <val>")?,
-            }
+            let (on, off) = settings.paint(settings.theme.placeholder);
+            write!(out, " {}<{}>{}", on, name.unwrap_or("val"), off)?;
         }
 
         Ok(())
     }
 }
 
+/// Additional name of an option, see [`Opt::aliases`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alias<'a> {
+    pub name: Name<'a>,
+    /// Visible aliases are listed in help if [`Settings::show_aliases`] is `true`, hidden ones
+    /// are only parsed.
+    pub visible: bool,
+}
+
+/// Name of an option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Name<'a> {
+    /// Short name, i.e. `-n`.
+    Short(char),
+    /// Long name, i.e. `--dry-run`.
+    Long(&'a str),
+}
+
+impl Display for Name<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Name::Short(s) => write!(f, "-{}", s),
+            Name::Long(l) => write!(f, "--{}", l),
+        }
+    }
+}
+
+/// Worked example, see `examples` of [`Description::Typed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example<'a> {
+    /// Command line, i.e. `cp -r src/ dst/`.
+    pub command: &'a str,
+    /// Explanation of what the command does.
+    pub descr: &'a str,
+}
+
+/// Environment variable, see `env` of [`Description::Typed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Env<'a> {
+    pub name: &'a str,
+    pub descr: &'a str,
+    /// Value used if the variable is not set.
+    pub default: Option<&'a str>,
+}
+
+/// Deprecation of an option, see [`Opt::deprecated`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deprecated<'a> {
+    /// Option which should be used instead, i.e. `--color`. Shown in the
+    /// [long help](Mode::Long).
+    pub replacement: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Required<'a> {
     Required,
     Optional,
     If(&'a str),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Kind<'a> {
     Value {
         name: Option<&'a str>,
@@ -217,3 +1768,664 @@ pub enum Kind<'a> {
     Flag,
     Count,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+
+    use crate::Token;
+
+    use super::{
+        resolve_width, Alias, DefaultRenderer, Deprecated, Description, Env, Example, Heading,
+        HelpRenderer, HelpRequest, Kind, Mode, Name, Opt, Pos, Required, Search, Settings,
+        MAX_WIDTH,
+    };
+
+    const DESCR: Description<'static> = Description::DEFAULT
+        .name("test")
+        .descr("Test command")
+        .usage("test [-v...] [-o <file>] <input>")
+        .positionals(&[Pos {
+            name: "input",
+            descr: "input file",
+            ..Pos::DEFAULT
+        }])
+        .options(&[
+            Opt {
+                short: Some('v'),
+                long: Some("verbose"),
+                kind: Kind::Count,
+                descr: "verbosity level",
+                required: Required::Optional,
+                ..Opt::DEFAULT
+            },
+            Opt {
+                short: Some('o'),
+                long: None,
+                kind: Kind::Value {
+                    name: Some("file"),
+                    default: Some("out.txt"),
+                },
+                descr: "output file",
+                required: Required::Optional,
+                ..Opt::DEFAULT
+            },
+        ]);
+
+    #[test]
+    fn render() {
+        let expected = "\
+Test command
+
+Usage:
+  test [-v...] [-o <file>] <input>
+
+Positional arguments:
+    input            input file
+
+Options:
+    -v, --verbose    verbosity level
+    -o <file>        output file [default: out.txt]
+";
+        assert_eq!(DESCR.to_string(), expected);
+        assert_eq!(format!("{:80}", DESCR), expected);
+
+        let mut buf = Vec::new();
+        DESCR.write(&mut buf, &Settings::default()).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), expected);
+
+        let mut s = String::new();
+        let settings = Settings {
+            width_limit: Some(50),
+            indent: 2,
+            ..Settings::default()
+        };
+        DESCR.render(&mut s, &settings).unwrap();
+        assert_eq!(
+            s,
+            "\
+Test command
+
+Usage:
+  test [-v...] [-o <file>] <input>
+
+Positional arguments:
+  input          input file
+
+Options:
+  -v, --verbose  verbosity level
+  -o <file>      output file [default: out.txt]
+"
+        );
+    }
+
+    #[test]
+    fn auto_width() {
+        assert_eq!(resolve_width(Some("60"), || Some(80)), Some(60));
+        assert_eq!(resolve_width(Some("600"), || Some(80)), Some(MAX_WIDTH));
+        assert_eq!(resolve_width(Some("oops"), || Some(80)), Some(80));
+        assert_eq!(resolve_width(Some("0"), || None), None);
+        assert_eq!(resolve_width(None, || Some(400)), Some(MAX_WIDTH));
+        assert_eq!(resolve_width(None, || None), None);
+    }
+
+    #[test]
+    fn styled() {
+        let settings = Settings {
+            width_limit: Some(40),
+            styled: true,
+            ..Settings::default()
+        };
+        let mut s = String::new();
+        DESCR.render(&mut s, &settings).unwrap();
+
+        assert!(s.contains("\x1b[1;4mOptions:\x1b[0m"));
+        assert!(s.contains("\x1b[1m-v\x1b[0m, \x1b[1m--verbose\x1b[0m"));
+        assert!(s.contains("\x1b[3m<file>\x1b[0m"));
+
+        // Escape sequences must not affect alignment
+        let mut stripped = String::new();
+        let mut chars = s.chars();
+        while let Some(ch) = chars.next() {
+            if ch == '\x1b' {
+                chars.by_ref().find(|&ch| ch == 'm');
+            } else {
+                stripped.push(ch);
+            }
+        }
+        assert_eq!(stripped, DESCR.to_string_with_width(40));
+    }
+
+    #[test]
+    fn narrow() {
+        for width in 0..40 {
+            let s = DESCR.to_string_with_width(width);
+            assert!(s.lines().all(|l| l.trim_end() == l));
+        }
+    }
+
+    #[test]
+    fn layout() {
+        let descr = Description::DEFAULT
+            .name("test")
+            .descr("Test command")
+            .usage("test")
+            .options(&[
+                Opt {
+                    short: None,
+                    long: Some("some-really-long-option"),
+                    kind: Kind::Value {
+                        name: Some("value"),
+                        default: None,
+                    },
+                    descr: "long option",
+                    required: Required::Optional,
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    short: Some('s'),
+                    long: None,
+                    kind: Kind::Flag,
+                    descr: "short option",
+                    required: Required::Optional,
+                    ..Opt::DEFAULT
+                },
+            ]);
+
+        let settings = Settings {
+            width_limit: Some(40),
+            max_name_width: 20,
+            ..Settings::default()
+        };
+        let mut s = String::new();
+        descr.render(&mut s, &settings).unwrap();
+        assert_eq!(
+            s,
+            "\
+Test command
+
+Usage:
+  test
+
+Options:
+    --some-really-long-option <value>
+          long option
+    -s    short option
+"
+        );
+
+        // not enough space for two columns
+        assert_eq!(
+            DESCR.to_string_with_width(30),
+            "\
+Test command
+
+Usage:
+  test [-v...] [-o <file>]
+  <input>
+
+Positional arguments:
+    input
+        input file
+
+Options:
+    -v, --verbose
+        verbosity level
+    -o <file>
+        output file [default:
+        out.txt]
+"
+        );
+    }
+
+    #[test]
+    fn sections() {
+        let descr = Description::DEFAULT
+            .name("test")
+            .descr("Test command")
+            .usage("test")
+            .sections(&["Network", "Output"])
+            .positionals(&[
+                Pos {
+                    name: "url",
+                    section: Some("Network"),
+                    ..Pos::DEFAULT
+                },
+                Pos {
+                    name: "input",
+                    ..Pos::DEFAULT
+                },
+            ])
+            .options(&[
+                Opt {
+                    long: Some("debug"),
+                    section: Some("Advanced"),
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    long: Some("output"),
+                    section: Some("Output"),
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    long: Some("proxy"),
+                    section: Some("Network"),
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    long: Some("help"),
+                    ..Opt::DEFAULT
+                },
+            ]);
+
+        assert_eq!(
+            descr.to_string(),
+            "\
+Test command
+
+Usage:
+  test
+
+Positional arguments:
+    input
+
+Options:
+    --help
+
+Network:
+    url
+    --proxy
+
+Output:
+    --output
+
+Advanced:
+    --debug
+"
+        );
+    }
+
+    #[test]
+    fn modes() {
+        let descr = Description::DEFAULT
+            .name("test")
+            .descr("Test command")
+            .long_descr("Test command.\n\nIt tests things.")
+            .usage("test")
+            .options(&[
+                Opt {
+                    long: Some("jobs"),
+                    descr: "number of jobs\ndefaults to the number of CPUs",
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    long: Some("debug"),
+                    descr: "debug things",
+                    long_descr: Some("debug things, a lot"),
+                    advanced: true,
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    long: Some("colour"),
+                    descr: "when to use colors",
+                    deprecated: Some(Deprecated {
+                        replacement: Some("--color"),
+                    }),
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    long: Some("secret"),
+                    hidden: true,
+                    ..Opt::DEFAULT
+                },
+            ]);
+
+        let render = |mode| {
+            let mut s = String::new();
+            let settings = Settings {
+                mode,
+                ..Settings::default()
+            };
+            descr.render(&mut s, &settings).unwrap();
+            s
+        };
+
+        assert_eq!(
+            render(Mode::Short),
+            "\
+Test command
+
+Usage:
+  test
+
+Options:
+    --jobs      number of jobs
+    --colour    when to use colors [deprecated]
+"
+        );
+        assert_eq!(
+            render(Mode::Long),
+            "\
+Test command.
+
+It tests things.
+
+Usage:
+  test
+
+Options:
+    --jobs      number of jobs
+                defaults to the number of CPUs
+    --debug     debug things, a lot
+    --colour    when to use colors [deprecated, use `--color` instead]
+"
+        );
+    }
+
+    #[test]
+    fn aliases() {
+        let descr = Description::DEFAULT
+            .name("test")
+            .descr("Test command")
+            .usage("test")
+            .options(&[Opt {
+                long: Some("color"),
+                descr: "when to use colors",
+                aliases: &[
+                    Alias {
+                        name: Name::Long("colour"),
+                        visible: true,
+                    },
+                    Alias {
+                        name: Name::Short('C'),
+                        visible: true,
+                    },
+                    Alias {
+                        name: Name::Long("colors"),
+                        visible: false,
+                    },
+                ],
+                ..Opt::DEFAULT
+            }]);
+
+        let render = |show_aliases| {
+            let mut s = String::new();
+            let settings = Settings {
+                show_aliases,
+                ..Settings::default()
+            };
+            descr.render(&mut s, &settings).unwrap();
+            s
+        };
+
+        assert_eq!(
+            render(true),
+            "\
+Test command
+
+Usage:
+  test
+
+Options:
+    --color    when to use colors [aliases: --colour, -C]
+"
+        );
+        assert!(!render(false).contains("aliases"));
+
+        for token in &[
+            Token::Long {
+                key: "color",
+                value: None,
+            },
+            Token::Long {
+                key: "colors",
+                value: None,
+            },
+            Token::Short {
+                key: 'C',
+                value: Some("never"),
+            },
+        ] {
+            assert!(descr.find_option(token).is_some(), "{:?}", token);
+        }
+        assert!(descr
+            .find_option(&Token::Short {
+                key: 'c',
+                value: None
+            })
+            .is_none());
+    }
+
+    #[test]
+    fn template() {
+        let descr = Description::DEFAULT
+            .name("test")
+            .descr("Test command")
+            .usage("test [-v...] [-o <file>] <input>")
+            .positionals(&[Pos {
+                name: "input",
+                descr: "input file",
+                ..Pos::DEFAULT
+            }])
+            .options(&[
+                Opt {
+                    short: Some('v'),
+                    descr: "verbosity level",
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    short: Some('o'),
+                    descr: "output file",
+                    section: Some("Output"),
+                    ..Opt::DEFAULT
+                },
+            ])
+            .after_help("See also: test(1)");
+
+        assert_eq!(
+            descr.to_string(),
+            "\
+Test command
+
+Usage:
+  test [-v...] [-o <file>] <input>
+
+Positional arguments:
+    input    input file
+
+Options:
+    -v       verbosity level
+
+Output:
+    -o       output file
+
+See also: test(1)
+"
+        );
+
+        let template = "\
+=== {name} ===
+{descr}
+
+usage: {usage}
+
+{positionals}{options}{sections}
+{after-help} {{unknown}} {unknown} {name
+";
+        assert_eq!(
+            format!("{:34}", descr.template(template)),
+            "\
+=== test ===
+Test command
+
+usage: test [-v...] [-o <file>]
+       <input>
+
+    input    input file
+    -v       verbosity level
+
+Output:
+    -o       output file
+
+See also: test(1) {unknown} {unknown} {name
+"
+        );
+    }
+
+    #[test]
+    fn renderer() {
+        struct House;
+
+        impl HelpRenderer for House {
+            fn heading(
+                &self,
+                out: &mut dyn fmt::Write,
+                _settings: &Settings,
+                heading: Heading<'_>,
+            ) -> fmt::Result {
+                write!(out, "{}", heading.to_string().to_uppercase())
+            }
+
+            fn positional_name(
+                &self,
+                out: &mut dyn fmt::Write,
+                _settings: &Settings,
+                pos: &Pos<'_>,
+            ) -> fmt::Result {
+                write!(out, "<{}>", pos.name.to_uppercase())
+            }
+        }
+
+        let mut s = String::new();
+        DESCR
+            .render_with(&House, &mut s, &Settings::default())
+            .unwrap();
+        assert_eq!(
+            s,
+            "\
+Test command
+
+USAGE
+  test [-v...] [-o <file>] <input>
+
+POSITIONAL ARGUMENTS
+    <INPUT>          input file
+
+OPTIONS
+    -v, --verbose    verbosity level
+    -o <file>        output file [default: out.txt]
+"
+        );
+    }
+
+    #[test]
+    fn extra() {
+        let descr = Description::DEFAULT
+            .name("cp")
+            .descr("Copies files")
+            .usage("cp [-v] <src> <dst>")
+            .options(&[Opt {
+                short: Some('v'),
+                descr: "explain what is being done",
+                ..Opt::DEFAULT
+            }])
+            .examples(&[
+                Example {
+                    command: "cp -v a.txt b.txt",
+                    descr: "copy a.txt to b.txt and explain it",
+                },
+                Example {
+                    command: "cp a.txt dir/",
+                    descr: "copy a.txt into dir",
+                },
+            ])
+            .env(&[
+                Env {
+                    name: "CP_BACKUP",
+                    descr: "suffix of backups",
+                    default: Some("~"),
+                },
+                Env {
+                    name: "NO_COLOR",
+                    descr: "disables colors",
+                    default: None,
+                },
+            ])
+            .after_help("See also: mv(1)");
+
+        assert_eq!(
+            format!("{:40}", descr),
+            "\
+Copies files
+
+Usage:
+  cp [-v] <src> <dst>
+
+Options:
+    -v    explain what is being done
+
+Examples:
+    cp -v a.txt b.txt
+        copy a.txt to b.txt and explain
+        it
+    cp a.txt dir/
+        copy a.txt into dir
+
+Environment variables:
+    CP_BACKUP    suffix of backups
+                 [default: ~]
+    NO_COLOR     disables colors
+
+See also: mv(1)
+"
+        );
+    }
+
+    #[test]
+    fn search() {
+        let search = |keyword, fuzzy| {
+            let mut s = String::new();
+            let search = Search { keyword, fuzzy };
+            DESCR
+                .render_search(&search, &DefaultRenderer, &mut s, &Settings::default())
+                .unwrap();
+            s
+        };
+
+        assert_eq!(
+            search("FILE", false),
+            "\
+Positional arguments:
+    input        input file
+
+Options:
+    -o <file>    output file [default: out.txt]
+"
+        );
+        assert_eq!(
+            search("vrbs", true),
+            "Options:\n    -v, --verbose    verbosity level\n"
+        );
+        assert_eq!(search("vrbs", false), "No options match `vrbs`.\n");
+        assert_eq!(
+            search("--rbo", false),
+            "Options:\n    -v, --verbose    verbosity level\n"
+        );
+        assert_eq!(
+            search("-o", false),
+            "Options:\n    -o <file>    output file [default: out.txt]\n"
+        );
+        assert_eq!(search("-rbo", false), "No options match `-rbo`.\n");
+
+        let request = HelpRequest::from_token(&Token::Short {
+            key: 'h',
+            value: Some("level"),
+        })
+        .unwrap();
+        let mut s = String::new();
+        request
+            .render(&DESCR, &mut s, &Settings::default())
+            .unwrap();
+        assert_eq!(s, "Options:\n    -v, --verbose    verbosity level\n");
+    }
+}
@@ -1,19 +1,33 @@
 // private, but reexported
 mod error;
 mod from_args;
+mod to_args;
 mod token;
 
-pub use error::{Error, OwnError, SwitchAlreadySetError, TooManyOptionsError};
+pub use error::{
+    Error, OwnError, OwnWarning, SwitchAlreadySetError, TooManyOptionsError, UnparseError, Warning,
+};
 pub use from_args::FromArgsIter;
-pub use token::{OwnToken, Parse, Token};
+pub use to_args::Args;
+pub use token::{
+    DashDashPolicy, NegativeNumbers, OwnToken, Parse, ParseConfig, Prefix, Token, ValuePolicy,
+};
 
+pub mod compat;
+pub mod complete;
 pub mod help;
+pub mod man;
+pub mod markdown;
 pub mod own;
+pub mod response;
+pub mod shell;
+pub mod term;
 pub mod tr;
 
-pub(crate) mod dumb_wrap;
+pub(crate) mod markup;
+pub(crate) mod wrap;
 
-use std::env;
+use std::{env, process::Command};
 
 use own::{FromArgsOwned, PollInitOwned};
 use tr::IntoOwned;
@@ -27,15 +41,36 @@ use crate::help::Description;
 /// Returns first error, if any has occured.
 ///
 /// Note: First element of the iteratpr is **not** ignored. If you are using [`env::args`] use `.skip(1)`.
+///
+/// [Warnings](Warning) are dropped, use [`from_args_with_warnings`] to get them.
 pub fn from_args<'a, T, A>(args: A) -> Result<T, Error<'a, <T::Init as PollInit<'a>>::Err>>
 where
     T: FromArgs<'a>,
     A: Iterator<Item = &'a str>,
 {
-    from_args_iter(args)
+    from_args_with_warnings(args).0
+}
+
+/// Like [`from_args`](from_args()), but also returns [warnings](Warning) which occured before
+/// the result (or the first error).
+#[allow(clippy::type_complexity)]
+pub fn from_args_with_warnings<'a, T, A>(
+    args: A,
+) -> (
+    Result<T, Error<'a, <T::Init as PollInit<'a>>::Err>>,
+    Vec<Warning<'a>>,
+)
+where
+    T: FromArgs<'a>,
+    A: Iterator<Item = &'a str>,
+{
+    let mut iter = from_args_iter(args);
+    let res = iter
         .next()
         // `FromArgsIter` always has at least 1 evlement
-        .unwrap()
+        .unwrap();
+
+    (res, iter.warnings)
 }
 
 /// Creates [`FromArgsIter`] from `Iterator<Item = &str>`.
@@ -45,6 +80,8 @@ where
 /// Note: First element of the `args` iterator is **not** ignored.
 /// If you are using [`env::args`] use `.skip(1)`.
 ///
+/// Non-fatal [warnings](Warning) are available via [`FromArgsIter::warnings`].
+///
 /// For more easy-to-use function see [`from_args`](from_args()), [`collect_from_args`] and [`from_env`]
 pub fn from_args_iter<'a, T, A>(args: A) -> FromArgsIter<'a, T, A>
 where
@@ -52,8 +89,9 @@ where
     A: Iterator<Item = &'a str>,
 {
     FromArgsIter {
-        parser: Token::parse(args),
+        parser: Token::parse_with(parse_config::<T>(), args),
         init: Some(T::initializer()),
+        warnings: Vec::new(),
     }
 }
 
@@ -62,6 +100,8 @@ where
 /// Returns all errors, if any has occured.
 ///
 /// Note: First element of the iteratpr is **not** ignored. If you are using [`env::args`] use `.skip(1)`.
+///
+/// [Warnings](Warning) are dropped, use [`collect_from_args_with_warnings`] to get them.
 pub fn collect_from_args<'a, T, A>(
     args: A,
 ) -> Result<T, Vec<crate::Error<'a, <T::Init as PollInit<'a>>::Err>>>
@@ -69,20 +109,33 @@ where
     T: FromArgs<'a>,
     A: Iterator<Item = &'a str>,
 {
-    let mut iter = from_args_iter(args);
-    let mut errors = match iter.next() {
-        None => return Err(Vec::new()),
-        Some(Ok(ok)) => return Ok(ok),
-        Some(Err(err)) => vec![err],
-    };
+    collect_from_args_with_warnings(args).0
+}
 
-    for res in iter {
-        if let Err(err) = res {
-            errors.push(err);
+/// Like [`collect_from_args`], but also returns all [warnings](Warning).
+#[allow(clippy::type_complexity)]
+pub fn collect_from_args_with_warnings<'a, T, A>(
+    args: A,
+) -> (
+    Result<T, Vec<crate::Error<'a, <T::Init as PollInit<'a>>::Err>>>,
+    Vec<Warning<'a>>,
+)
+where
+    T: FromArgs<'a>,
+    A: Iterator<Item = &'a str>,
+{
+    let mut iter = from_args_iter(args);
+    let mut errors = Vec::new();
+    let res = loop {
+        match iter.next() {
+            Some(Err(err)) => errors.push(err),
+            Some(Ok(ok)) if errors.is_empty() => break Ok(ok),
+            // `T::finish` may succeed even after errors
+            Some(Ok(_)) | None => break Err(errors),
         }
-    }
+    };
 
-    Err(errors)
+    (res, iter.warnings)
 }
 
 /// Creates `T` from [`env::args`].
@@ -91,25 +144,85 @@ where
 ///
 /// 1. Executable path is ignored.
 /// 2. `T` can't borrow anything from args.
+/// 3. [Warnings](Warning) are dropped, use [`from_env_with_warnings`] to get them.
 pub fn from_env<T>() -> Result<T, Vec<OwnError<<T::OwnInit as PollInitOwned>::OwnErr>>>
+where
+    T: FromArgsOwned,
+{
+    from_env_with_warnings().0
+}
+
+/// Like [`from_env`], but also returns all [warnings](Warning).
+///
+/// ## Examples
+///
+/// ```no_run
+/// # fn run<T: vvvv::own::FromArgsOwned>() {
+/// let (res, warnings) = vvvv::from_env_with_warnings::<T>();
+/// for warning in warnings {
+///     eprintln!("warning: {}", warning);
+/// }
+/// # }
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn from_env_with_warnings<T>() -> (
+    Result<T, Vec<OwnError<<T::OwnInit as PollInitOwned>::OwnErr>>>,
+    Vec<OwnWarning>,
+)
 where
     T: FromArgsOwned,
 {
     let args: Vec<_> = env::args().skip(1).collect();
-    let mut iter = from_args_iter(args.iter().map(String::as_str));
-    let mut errors = match iter.next() {
-        None => return Err(Vec::new()),
-        Some(Ok(ok)) => return Ok(ok),
-        Some(Err(err)) => vec![err.into_owned()],
-    };
+    let (res, warnings) = collect_from_args_with_warnings(args.iter().map(String::as_str));
 
-    for res in iter {
-        if let Err(err) = res {
-            errors.push(err.into_owned());
-        }
-    }
+    (
+        res.map_err(|errors| errors.into_iter().map(IntoOwned::into_owned).collect()),
+        warnings.into_iter().map(IntoOwned::into_owned).collect(),
+    )
+}
+
+/// Turns `value` back into command line arguments, see [`ToArgs`].
+///
+/// Returns an error if a token can't be written so that it's parsed back the same way, i.e. the
+/// value `-x` of an option if [`FromArgs::PARSE_CONFIG`] doesn't [split](ParseConfig::split_eq)
+/// at `=`.
+pub fn to_args<'a, T>(value: &T) -> Result<Vec<String>, UnparseError>
+where
+    T: ToArgs<'a>,
+{
+    let mut args = Args::new();
+    value.to_args(&mut args);
+    args.normalize(&T::DESCRIPTION);
+    Token::unparse_with(parse_config::<T>(), &args.tokens)
+}
 
-    Err(errors)
+/// Returns the [resolved](ParseConfig::resolve) lexing rules of `T`, shared by parsing and
+/// unparsing so that they agree.
+fn parse_config<'a, T: FromArgs<'a>>() -> ParseConfig {
+    T::PARSE_CONFIG.resolve(&T::DESCRIPTION)
+}
+
+/// Appends arguments representing `value` to the `command`, see [`to_args`](to_args()).
+///
+/// ```no_run
+/// # fn spawn<'a, T: vvvv::ToArgs<'a>>(worker_opts: &T) -> std::io::Result<()> {
+/// use std::process::Command;
+///
+/// let mut worker = Command::new("worker");
+/// vvvv::command_args(&mut worker, worker_opts)
+///     .expect("options can be written as arguments")
+///     .spawn()?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn command_args<'c, 'a, T>(
+    command: &'c mut Command,
+    value: &T,
+) -> Result<&'c mut Command, UnparseError>
+where
+    T: ToArgs<'a>,
+{
+    Ok(command.args(to_args(value)?))
 }
 
 /// Type that can be created from command line arguments.
@@ -118,6 +231,18 @@ where
 pub trait FromArgs<'a>: Sized {
     const DESCRIPTION: Description<'static>;
 
+    /// Lexing rules used to split arguments into [tokens](Token).
+    ///
+    /// Defaults to [`ParseConfig::DEFAULT`] with [`NegativeNumbers::Auto`], which is
+    /// [resolved](ParseConfig::resolve) against [`DESCRIPTION`](FromArgs::DESCRIPTION), and
+    /// [split at `=`](ParseConfig::split_eq), so `--out=file` works and [`to_args`](to_args())
+    /// can write values starting with `-`. Use
+    /// [`ParseConfig::posixly_correct`] to let the `POSIXLY_CORRECT` environment variable turn
+    /// the [POSIX mode](ParseConfig::posix) on.
+    const PARSE_CONFIG: ParseConfig = ParseConfig::DEFAULT
+        .negative_numbers(NegativeNumbers::Auto)
+        .split_eq(true);
+
     /// Initializer of this type which holds possibly uninitialized data.
     type Init: PollInit<'a, Output = Self>;
 
@@ -136,12 +261,29 @@ pub trait PollInit<'a> {
     /// Sink `token` to progress in initializing `Self::Output`.
     fn poll_init(&mut self, token: Token<'a>) -> Result<(), crate::Error<'a, Self::Err>>;
 
+    /// Returns `true` if all following arguments should be parsed as positionals, i.e. after a
+    /// subcommand name.
+    ///
+    /// Checked after every successful [`poll_init`](PollInit::poll_init).
+    fn stop_options(&self) -> bool {
+        false
+    }
+
     /// Finish the initialization.
     ///
     /// Returns error if output type can't be created from given tokens.
     fn finish(self) -> Result<Self::Output, crate::Error<'a, Self::Err>>;
 }
 
+/// Type that can be turned back into command line arguments, the inverse of [`FromArgs`].
+///
+/// Implementations write every field to [`Args`] so that `from_args(to_args(&x)) == x`: flags
+/// only when they are set, counters repeated, values with their option. Use [`to_args`](to_args()) or [`command_args`] to get the arguments.
+pub trait ToArgs<'a>: FromArgs<'a> {
+    /// Writes `self` to the `args`.
+    fn to_args(&self, args: &mut Args);
+}
+
 #[doc(hidden)]
 pub fn try_insert<T, E>(
     opt: &mut Option<T>,
@@ -161,9 +303,12 @@ pub fn try_insert<T, E>(
 mod tests {
     use std::str::FromStr;
 
+    use proptest::prelude::*;
+
     use crate::{
-        help::Description, help::Kind, help::Opt, help::Required, tr::Counter, tr::Switch,
-        try_insert, Error, FromArgs, PollInit, Token,
+        help::Alias, help::Deprecated, help::Description, help::Kind, help::Name, help::Opt,
+        help::Required, tr::Counter, tr::IntoOwned, tr::Switch, try_insert, Args, Error, FromArgs,
+        ParseConfig, PollInit, ToArgs, Token, ValuePolicy, Warning,
     };
 
     #[derive(Debug, Eq, PartialEq)]
@@ -177,11 +322,11 @@ mod tests {
 
     // Imagine it's generated via derive
     impl<'a> FromArgs<'a> for Test {
-        const DESCRIPTION: Description<'static> = Description::Typed {
-            descr: "Test command",
-            usage: "test -a <val> -b <val> [-c] [-d...] [-x <val>]",
-            positionals: &[],
-            options: &[
+        const DESCRIPTION: Description<'static> = Description::DEFAULT
+            .name("test")
+            .descr("Test command")
+            .usage("test -a <val> -b <val> [-c] [-d...] [-x <val>]")
+            .options(&[
                 Opt {
                     short: Some('a'),
                     long: None,
@@ -191,6 +336,7 @@ mod tests {
                     },
                     descr: "raw",
                     required: Required::Required,
+                    ..Opt::DEFAULT
                 },
                 Opt {
                     short: Some('b'),
@@ -201,6 +347,7 @@ mod tests {
                     },
                     descr: "parse",
                     required: Required::Required,
+                    ..Opt::DEFAULT
                 },
                 Opt {
                     short: Some('c'),
@@ -208,6 +355,7 @@ mod tests {
                     kind: Kind::Flag,
                     descr: "switch/flag",
                     required: Required::Optional,
+                    ..Opt::DEFAULT
                 },
                 Opt {
                     short: Some('d'),
@@ -215,6 +363,7 @@ mod tests {
                     kind: Kind::Count,
                     descr: "count",
                     required: Required::Required,
+                    ..Opt::DEFAULT
                 },
                 Opt {
                     short: Some('x'),
@@ -225,9 +374,9 @@ mod tests {
                     },
                     descr: "optional",
                     required: Required::Optional,
+                    ..Opt::DEFAULT
                 },
-            ],
-        };
+            ]);
 
         type Init = TestInit;
 
@@ -253,7 +402,7 @@ mod tests {
         x: Option<Option<String>>, // default+wrap(Some)
     }
 
-    #[allow(non_camel_case_types)]
+    #[allow(non_camel_case_types, dead_code)]
     #[derive(Debug)]
     enum TestParseError {
         a(<String as FromStr>::Err),
@@ -361,6 +510,7 @@ mod tests {
 
                 t @ Token::Short { .. } => Err(Error::UnknownOption(t)),
                 t @ Token::Long { .. } => Err(Error::UnknownOption(t)),
+                t @ Token::Keyed { .. } => Err(Error::UnknownOption(t)),
 
                 Token::DashDash => {
                     self.term = true;
@@ -407,9 +557,419 @@ mod tests {
         )
     }
 
+    #[derive(Debug)]
+    struct Legacy<'a>(Vec<&'a str>);
+
+    impl<'a> FromArgs<'a> for Legacy<'a> {
+        const DESCRIPTION: Description<'static> = Description::DEFAULT.options(&[
+            Opt {
+                long: Some("color"),
+                ..Opt::DEFAULT
+            },
+            Opt {
+                long: Some("colour"),
+                deprecated: Some(Deprecated {
+                    replacement: Some("--color"),
+                }),
+                ..Opt::DEFAULT
+            },
+        ]);
+
+        type Init = Legacy<'a>;
+
+        fn initializer() -> Self::Init {
+            Legacy(Vec::new())
+        }
+    }
+
+    impl<'a> PollInit<'a> for Legacy<'a> {
+        type Output = Self;
+
+        type Err = std::convert::Infallible;
+
+        fn poll_init(&mut self, token: Token<'a>) -> Result<(), crate::Error<'a, Self::Err>> {
+            match token {
+                t @ Token::Long { key, value: None }
+                    if Legacy::DESCRIPTION.find_option(&t).is_some() =>
+                {
+                    self.0.push(key)
+                }
+                t => return Err(Error::UnknownOption(t)),
+            }
+
+            Ok(())
+        }
+
+        fn finish(self) -> Result<Self::Output, crate::Error<'a, Self::Err>> {
+            Ok(self)
+        }
+    }
+
+    #[test]
+    fn deprecated() {
+        const COLOUR: Warning<'static> = Warning::Deprecated {
+            token: Token::Long {
+                key: "colour",
+                value: None,
+            },
+            replacement: Some("--color"),
+        };
+
+        let args = ["--color", "--colour"];
+        let mut iter = crate::from_args_iter::<Legacy, _>(args.iter().copied());
+        assert!(iter.next().unwrap().is_ok());
+        assert_eq!(iter.warnings(), [COLOUR]);
+
+        let (res, warnings) = crate::from_args_with_warnings::<Legacy, _>(args.iter().copied());
+        assert_eq!(res.unwrap().0, ["color", "colour"]);
+        assert_eq!(warnings, [COLOUR]);
+
+        // warnings are kept even if parsing fails
+        let args = ["--colour", "--x", "--colour"];
+        let (res, warnings) =
+            crate::collect_from_args_with_warnings::<Legacy, _>(args.iter().copied());
+        assert_eq!(res.unwrap_err().len(), 1);
+        assert_eq!(warnings, [COLOUR, COLOUR]);
+        assert_eq!(
+            warnings[0].into_owned().to_string(),
+            "Option `--colour` is deprecated, use `--color` instead"
+        );
+    }
+
+    impl<'a> ToArgs<'a> for Legacy<'a> {
+        fn to_args(&self, args: &mut Args) {
+            for key in &self.0 {
+                args.flag(Name::Long(key), true);
+            }
+        }
+    }
+
+    impl<'a> ToArgs<'a> for Test {
+        fn to_args(&self, args: &mut Args) {
+            args.value(Name::Short('a'), &self.a)
+                .value(Name::Short('b'), self.b)
+                .flag(Name::Short('c'), self.c)
+                .count(Name::Short('d'), self.d as usize);
+            if let Some(x) = &self.x {
+                args.value(Name::Short('x'), x);
+            }
+        }
+    }
+
+    #[test]
+    fn to_args() {
+        let test = Test {
+            a: String::from("a b"),
+            b: -42,
+            c: true,
+            d: 3,
+            x: Some(String::from("-")),
+        };
+        let args = crate::to_args(&test).unwrap();
+        assert_eq!(args, ["-a", "a b", "-b", "-42", "-cddd", "-x", "-"]);
+        assert_eq!(
+            crate::from_args::<Test, _>(args.iter().map(String::as_str)).unwrap(),
+            test
+        );
+
+        // values which look like options are joined with `=`
+        let test = Test {
+            a: String::from("-a"),
+            ..test
+        };
+        let args = crate::to_args(&test).unwrap();
+        assert_eq!(args, ["-a=-a", "-b", "-42", "-cddd", "-x", "-"]);
+        assert_eq!(
+            crate::from_args::<Test, _>(args.iter().map(String::as_str)).unwrap(),
+            test
+        );
+    }
+
+    #[test]
+    fn to_args_alias() {
+        // a deprecated option is not an alias, it's written as is
+        let legacy = Legacy(vec!["colour", "color"]);
+        assert_eq!(crate::to_args(&legacy).unwrap(), ["--colour", "--color"]);
+
+        let cp = Cp {
+            verbose: true,
+            out: Some("-x"),
+            files: vec!["a", "-b"],
+        };
+        let args = crate::to_args(&cp).unwrap();
+        assert_eq!(args, ["--verbose", "-o=-x", "--", "a", "-b"]);
+        assert_eq!(
+            crate::from_args::<Cp, _>(args.iter().map(String::as_str)).unwrap(),
+            cp
+        );
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Cp<'a> {
+        verbose: bool,
+        out: Option<&'a str>,
+        files: Vec<&'a str>,
+    }
+
+    impl<'a> FromArgs<'a> for Cp<'a> {
+        const DESCRIPTION: Description<'static> = Description::DEFAULT.options(&[
+            Opt {
+                short: Some('v'),
+                long: Some("verbose"),
+                aliases: &[Alias {
+                    name: Name::Long("loud"),
+                    visible: false,
+                }],
+                ..Opt::DEFAULT
+            },
+            Opt {
+                short: Some('o'),
+                long: Some("out"),
+                kind: Kind::Value {
+                    name: None,
+                    default: None,
+                },
+                ..Opt::DEFAULT
+            },
+        ]);
+
+        type Init = Cp<'a>;
+
+        fn initializer() -> Self::Init {
+            Cp {
+                verbose: false,
+                out: None,
+                files: Vec::new(),
+            }
+        }
+    }
+
+    impl<'a> PollInit<'a> for Cp<'a> {
+        type Output = Self;
+
+        type Err = std::convert::Infallible;
+
+        fn poll_init(&mut self, token: Token<'a>) -> Result<(), crate::Error<'a, Self::Err>> {
+            let long = Cp::DESCRIPTION.find_option(&token).and_then(|opt| opt.long);
+            match (token, long) {
+                (Token::Positional(file), _) => self.files.push(file),
+                (Token::DashDash, _) => {}
+                (Token::Short { value, .. }, Some(name))
+                | (Token::Long { value, .. }, Some(name)) => match (name, value) {
+                    ("verbose", None) => self.verbose = true,
+                    ("out", Some(out)) => self.out = Some(out),
+                    _ => return Err(Error::UnknownOption(token)),
+                },
+                (t, _) => return Err(Error::UnknownOption(t)),
+            }
+
+            Ok(())
+        }
+
+        fn finish(self) -> Result<Self::Output, crate::Error<'a, Self::Err>> {
+            Ok(self)
+        }
+    }
+
+    impl<'a> ToArgs<'a> for Cp<'a> {
+        fn to_args(&self, args: &mut Args) {
+            // `--loud` is an alias of `--verbose`
+            args.flag(Name::Long("loud"), self.verbose);
+            if let Some(out) = self.out {
+                args.value(Name::Short('o'), out);
+            }
+            args.dash_dash();
+            for file in &self.files {
+                args.positional(file);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn to_args_roundtrip(
+            a in "[a-z -]{0,4}",
+            b in any::<i32>(),
+            c in any::<bool>(),
+            d in 0u32..4,
+            x in proptest::option::of("[a-z =-]{0,4}"),
+        ) {
+            let test = Test { a, b, c, d, x };
+            let args = crate::to_args(&test).unwrap();
+            let parsed = crate::from_args::<Test, _>(args.iter().map(String::as_str));
+            prop_assert_eq!(parsed.unwrap(), test);
+        }
+
+        #[test]
+        fn to_args_positionals_roundtrip(
+            verbose in any::<bool>(),
+            out in proptest::option::of("[a-z =-]{0,3}"),
+            files in proptest::collection::vec("[a-z =-]{0,3}", 0..4),
+        ) {
+            let cp = Cp {
+                verbose,
+                out: out.as_deref(),
+                files: files.iter().map(String::as_str).collect(),
+            };
+            let args = crate::to_args(&cp).unwrap();
+            let parsed = crate::from_args::<Cp, _>(args.iter().map(String::as_str));
+            prop_assert_eq!(parsed.unwrap(), cp);
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Wrapper<'a> {
+        verbose: bool,
+        command: Vec<&'a str>,
+    }
+
+    impl<'a> FromArgs<'a> for Wrapper<'a> {
+        const DESCRIPTION: Description<'static> = Description::None;
+
+        const PARSE_CONFIG: ParseConfig = ParseConfig::DEFAULT.values(ValuePolicy::Attached);
+
+        type Init = Wrapper<'a>;
+
+        fn initializer() -> Self::Init {
+            Wrapper {
+                verbose: false,
+                command: Vec::new(),
+            }
+        }
+    }
+
+    impl<'a> PollInit<'a> for Wrapper<'a> {
+        type Output = Self;
+
+        type Err = ();
+
+        fn poll_init(&mut self, token: Token<'a>) -> Result<(), crate::Error<'a, Self::Err>> {
+            match token {
+                Token::Positional(arg) => self.command.push(arg),
+                Token::Short {
+                    key: 'v',
+                    value: None,
+                } => self.verbose = true,
+                t => return Err(Error::UnknownOption(t)),
+            }
+
+            Ok(())
+        }
+
+        fn stop_options(&self) -> bool {
+            !self.command.is_empty()
+        }
+
+        fn finish(self) -> Result<Self::Output, crate::Error<'a, Self::Err>> {
+            Ok(self)
+        }
+    }
+
+    #[test]
+    fn stop_options() {
+        let args = ["-v", "cmd", "-v", "--x"];
+        assert_eq!(
+            crate::from_args::<Wrapper, _>(args.iter().copied()).unwrap(),
+            Wrapper {
+                verbose: true,
+                command: vec!["cmd", "-v", "--x"],
+            }
+        );
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Longs<'a>(Vec<&'a str>);
+
+    impl<'a> FromArgs<'a> for Longs<'a> {
+        const DESCRIPTION: Description<'static> = Description::DEFAULT.options(&[
+            Opt {
+                long: Some("verbose"),
+                ..Opt::DEFAULT
+            },
+            Opt {
+                long: Some("version"),
+                ..Opt::DEFAULT
+            },
+            Opt {
+                long: Some("color"),
+                aliases: &[Alias {
+                    name: Name::Long("colour"),
+                    visible: false,
+                }],
+                ..Opt::DEFAULT
+            },
+        ]);
+
+        const PARSE_CONFIG: ParseConfig = ParseConfig::DEFAULT
+            .abbreviations(true)
+            .single_dash_long(true);
+
+        type Init = Longs<'a>;
+
+        fn initializer() -> Self::Init {
+            Longs(Vec::new())
+        }
+    }
+
+    impl<'a> PollInit<'a> for Longs<'a> {
+        type Output = Self;
+
+        type Err = std::convert::Infallible;
+
+        fn poll_init(&mut self, token: Token<'a>) -> Result<(), crate::Error<'a, Self::Err>> {
+            match token {
+                Token::Long { key, value: None }
+                | Token::Keyed {
+                    key, value: None, ..
+                } if Longs::DESCRIPTION.find_option(&token).is_some() => self.0.push(key),
+                t => return Err(Error::UnknownOption(t)),
+            }
+
+            Ok(())
+        }
+
+        fn finish(self) -> Result<Self::Output, crate::Error<'a, Self::Err>> {
+            Ok(self)
+        }
+    }
+
+    #[test]
+    fn abbreviations() {
+        let args = ["--verb", "--colo", "--colou", "--version", "--x"];
+        let mut iter = crate::from_args_iter::<Longs, _>(args.iter().copied());
+        assert!(matches!(
+            iter.next(),
+            Some(Err(Error::UnknownOption(Token::Long { key: "x", .. })))
+        ));
+        assert_eq!(
+            iter.next().unwrap().unwrap(),
+            Longs(vec!["verbose", "color", "colour", "version"])
+        );
+
+        let args = ["--ver"];
+        let err = crate::from_args::<Longs, _>(args.iter().copied()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Ambiguous option: `--ver`, could be `--verbose`, `--version`"
+        );
+
+        let args = ["-verb", "-colour"];
+        assert_eq!(
+            crate::from_args::<Longs, _>(args.iter().copied()).unwrap(),
+            Longs(vec!["verbose", "colour"])
+        );
+        let args = ["-ver"];
+        let err = crate::from_args::<Longs, _>(args.iter().copied()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Ambiguous option: `-ver`, could be `-verbose`, `-version`"
+        );
+    }
+
     #[allow(dead_code)]
     fn from_env_is_callable() {
         let _: Test = crate::from_env().unwrap();
+        let (_, _): (Result<Test, _>, Vec<crate::OwnWarning>) = crate::from_env_with_warnings();
     }
 
     // #[test]
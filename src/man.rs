@@ -0,0 +1,525 @@
+//! Man pages generated from [typed descriptions](Description::Typed), written in roff like
+//! `man 7 man` describes.
+//!
+//! ```
+//! use vvvv::{
+//!     help::{Description, Opt},
+//!     man::{self, Page},
+//! };
+//!
+//! let descr = Description::DEFAULT
+//!     .name("cp")
+//!     .descr("Copies files")
+//!     .options(&[Opt {
+//!         short: Some('v'),
+//!         descr: "explain what is being done",
+//!         ..Opt::DEFAULT
+//!     }]);
+//!
+//! let mut page = String::new();
+//! man::render(&descr, &Page::DEFAULT, &mut page).unwrap();
+//! assert!(page.starts_with(".TH \"CP\" \"1\"\n.SH NAME\ncp \\- Copies files\n"));
+//! assert!(page.contains(".SH OPTIONS\n.TP\n\\fB\\-v\\fR\nexplain what is being done\n"));
+//! ```
+//!
+//! The page has the same parts as the [long help](crate::help::Mode::Long): positional
+//! arguments are listed under `ARGUMENTS`, options under `OPTIONS` and named
+//! [sections](Opt::section) under their own headings, in the same order. Hidden options are left
+//! out. [Examples](Description::Typed::examples), [environment variables](Description::Typed::env)
+//! and the [text after the help](Description::Typed::after_help) follow under `EXAMPLES`,
+//! `ENVIRONMENT` and `NOTES`.
+
+use std::fmt;
+
+use crate::{
+    help::{self, Description, Env, Example, Kind, Opt, Pos},
+    markup::{self, Line, Span},
+};
+
+/// Header of a man page, see [`render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page<'a> {
+    /// Section of the manual, i.e. `1` for commands.
+    pub section: &'a str,
+    /// Date of the last change, i.e. `2024-01-31`.
+    pub date: Option<&'a str>,
+    /// Source of the program, i.e. its name and version.
+    pub source: Option<&'a str>,
+    /// Title of the manual, i.e. `User Commands`.
+    pub manual: Option<&'a str>,
+}
+
+impl Page<'_> {
+    /// Header of a page in the section `1` without date, source and manual.
+    pub const DEFAULT: Self = Self {
+        section: "1",
+        date: None,
+        source: None,
+        manual: None,
+    };
+}
+
+/// Renders the man page of `descr` to `out`.
+///
+/// [`Description::Raw`] is written as is, without filling, under `DESCRIPTION`.
+pub fn render(descr: &Description<'_>, page: &Page<'_>, out: &mut dyn fmt::Write) -> fmt::Result {
+    let (name, descr_, long_descr, sections, positionals, options, examples, env, after_help) =
+        match descr {
+            Description::None => return Ok(()),
+            Description::Raw(text) => {
+                header(out, "", page)?;
+                out.write_str(".SH DESCRIPTION\n.nf\n")?;
+                text_lines(out, text)?;
+                return out.write_str(".fi\n");
+            }
+            Description::Typed {
+                name,
+                descr,
+                long_descr,
+                sections,
+                positionals,
+                options,
+                examples,
+                env,
+                after_help,
+                ..
+            } => (
+                name,
+                descr,
+                long_descr,
+                sections,
+                positionals,
+                options,
+                examples,
+                env,
+                after_help,
+            ),
+        };
+
+    header(out, name, page)?;
+
+    out.write_str(".SH NAME\n")?;
+    match (name.is_empty(), descr_.is_empty()) {
+        (_, true) => text_lines(out, name)?,
+        (true, false) => text_lines(out, &plain(descr_))?,
+        (false, false) => text_lines(out, &format!("{} - {}", name, plain(descr_)))?,
+    }
+
+    out.write_str(".SH SYNOPSIS\n")?;
+    text_lines(out, &descr.usage_line())?;
+
+    if let Some(long_descr) = long_descr {
+        out.write_str(".SH DESCRIPTION\n")?;
+        text(out, long_descr, false)?;
+    }
+
+    let options: Vec<_> = options.iter().filter(|opt| !opt.hidden).copied().collect();
+    let positionals_in = |section| -> Vec<_> {
+        positionals
+            .iter()
+            .filter(|pos| pos.section == section)
+            .copied()
+            .collect()
+    };
+    let options_in = |section| -> Vec<_> {
+        options
+            .iter()
+            .filter(|opt| opt.section == section)
+            .copied()
+            .collect()
+    };
+
+    section(out, "ARGUMENTS", &positionals_in(None), &[])?;
+    section(out, "OPTIONS", &[], &options_in(None))?;
+    for name in help::section_order(sections, positionals, &options) {
+        let heading = name.to_uppercase();
+        let section_ = Some(name);
+        section(
+            out,
+            &heading,
+            &positionals_in(section_),
+            &options_in(section_),
+        )?;
+    }
+
+    if !examples.is_empty() {
+        out.write_str(".SH EXAMPLES\n")?;
+        for example in *examples {
+            out.write_str(".TP\n")?;
+            example_row(out, example)?;
+        }
+    }
+
+    if !env.is_empty() {
+        out.write_str(".SH ENVIRONMENT\n")?;
+        for env in *env {
+            out.write_str(".TP\n")?;
+            env_row(out, env)?;
+        }
+    }
+
+    if let Some(after_help) = after_help {
+        out.write_str(".SH NOTES\n")?;
+        text(out, after_help, false)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the `.TH` line.
+fn header(out: &mut dyn fmt::Write, name: &str, page: &Page<'_>) -> fmt::Result {
+    out.write_str(".TH")?;
+    let fields = [Some(&*name.to_uppercase()), Some(page.section)];
+    let optional = [page.date, page.source, page.manual];
+    let len = optional
+        .iter()
+        .rposition(Option::is_some)
+        .map_or(0, |i| i + 1);
+    for field in fields.iter().chain(&optional[..len]) {
+        write!(
+            out,
+            " \"{}\"",
+            escape(field.unwrap_or("")).replace('"', "\\(dq")
+        )?;
+    }
+    out.write_char('\n')
+}
+
+/// Writes a `.SH` heading followed by a `.TP` paragraph per positional argument and option,
+/// nothing if there are neither.
+fn section(
+    out: &mut dyn fmt::Write,
+    heading: &str,
+    positionals: &[Pos<'_>],
+    options: &[Opt<'_>],
+) -> fmt::Result {
+    if positionals.is_empty() && options.is_empty() {
+        return Ok(());
+    }
+
+    match heading.contains(char::is_whitespace) {
+        true => writeln!(out, ".SH \"{}\"", escape(heading).replace('"', "\\(dq"))?,
+        false => writeln!(out, ".SH {}", escape(heading))?,
+    }
+
+    for pos in positionals {
+        writeln!(out, ".TP\n\\fI{}\\fR", escape(pos.name))?;
+        text(out, pos.long_descr.unwrap_or(pos.descr), true)?;
+    }
+
+    for opt in options {
+        out.write_str(".TP\n")?;
+        option_name(out, opt)?;
+        text(out, &opt.long_text(), true)?;
+    }
+
+    Ok(())
+}
+
+/// Writes names of an option and its value placeholder, i.e. `\fB\-o\fR, \fB\-\-output\fR
+/// \fI<file>\fR`, ending with a newline.
+fn option_name(out: &mut dyn fmt::Write, opt: &Opt<'_>) -> fmt::Result {
+    let names = opt.short.map(help::Name::Short);
+    let names = names.into_iter().chain(opt.long.map(help::Name::Long));
+    for (i, name) in names.enumerate() {
+        let sep = if i == 0 { "" } else { ", " };
+        write!(out, "{}\\fB{}\\fR", sep, escape(&name.to_string()))?;
+    }
+
+    if let Kind::Value { name, .. } = opt.kind {
+        write!(out, " \\fI<{}>\\fR", escape(name.unwrap_or("val")))?;
+    }
+
+    out.write_char('\n')
+}
+
+/// Writes the command of an example in bold followed by its explanation.
+fn example_row(out: &mut dyn fmt::Write, example: &Example<'_>) -> fmt::Result {
+    writeln!(out, "\\fB{}\\fR", escape(example.command))?;
+    text(out, example.descr, true)
+}
+
+/// Writes the name of an environment variable in bold followed by its description and default
+/// value.
+fn env_row(out: &mut dyn fmt::Write, env: &Env<'_>) -> fmt::Result {
+    writeln!(out, "\\fB{}\\fR", escape(env.name))?;
+    match env.default {
+        Some(def) => text(out, &format!("{} [default: {}]", env.descr, def), true),
+        None => text(out, env.descr, true),
+    }
+}
+
+/// Writes `text` with [markup](Opt::descr), ending with a newline.
+///
+/// Empty lines start paragraphs, lines of a paragraph are broken with `.br` like in the help and
+/// bullets become `.IP` paragraphs. `nested` text is the body of a `.TP` paragraph, so its
+/// paragraphs keep the indentation and bullets are indented with `.RS`.
+fn text(out: &mut dyn fmt::Write, text: &str, nested: bool) -> fmt::Result {
+    let paragraph = if nested { ".IP\n" } else { ".PP\n" };
+    let mut prev = None;
+
+    for line in markup::lines(text.trim_end_matches('\n')) {
+        match line {
+            // an empty line after bullets ends them anyway
+            Line::Blank => {
+                if let Some(Line::Text(_)) = prev {
+                    prev = Some(Line::Blank);
+                }
+                continue;
+            }
+            Line::Bullet(bullet) => {
+                if nested && !matches!(prev, Some(Line::Bullet(_))) {
+                    out.write_str(".RS\n")?;
+                }
+                out.write_str(".IP \\(bu 2\n")?;
+                inline(out, bullet)?;
+            }
+            Line::Text(line) => {
+                match prev {
+                    Some(Line::Bullet(_)) if nested => {
+                        out.write_str(".RE\n")?;
+                        out.write_str(paragraph)?;
+                    }
+                    Some(Line::Bullet(_)) | Some(Line::Blank) => out.write_str(paragraph)?,
+                    Some(Line::Text(_)) => out.write_str(".br\n")?,
+                    None => {}
+                }
+                inline(out, line)?;
+            }
+        }
+        prev = Some(line);
+    }
+
+    if let (Some(Line::Bullet(_)), true) = (prev, nested) {
+        out.write_str(".RE\n")?;
+    }
+
+    Ok(())
+}
+
+/// Writes a line with code spans in bold and emphasis in italics, ending with a newline.
+fn inline(out: &mut dyn fmt::Write, line: &str) -> fmt::Result {
+    let mut text = String::new();
+    for span in markup::spans(line) {
+        match span {
+            Span::Text(plain) => text.push_str(&escape(plain)),
+            Span::Code(code) => text.push_str(&format!("\\fB{}\\fR", escape(code))),
+            Span::Emphasis(em) => text.push_str(&format!("\\fI{}\\fR", escape(em))),
+        }
+    }
+
+    text_line(out, &text)
+}
+
+/// Returns `text` without markup, for the `NAME` section which is read by `whatis`.
+fn plain(text: &str) -> String {
+    let lines = text.split('\n').map(|line| {
+        markup::spans(line)
+            .into_iter()
+            .map(|span| match span {
+                Span::Text(text) | Span::Code(text) | Span::Emphasis(text) => text,
+            })
+            .collect::<String>()
+    });
+    lines.collect::<Vec<_>>().join("\n")
+}
+
+/// Writes escaped `text` ending with a newline.
+fn text_lines(out: &mut dyn fmt::Write, text: &str) -> fmt::Result {
+    for line in text.lines() {
+        text_line(out, &escape(line))?;
+    }
+
+    Ok(())
+}
+
+/// Writes an escaped line of text, escaping a `.` or `'` at the start which would make it a
+/// request.
+fn text_line(out: &mut dyn fmt::Write, line: &str) -> fmt::Result {
+    if line.starts_with(['.', '\'']) {
+        out.write_str("\\&")?;
+    }
+    writeln!(out, "{}", line)
+}
+
+/// Escapes backslashes and dashes.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' => out.push_str("\\e"),
+            '-' => out.push_str("\\-"),
+            ch => out.push(ch),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, Page};
+    use crate::help::{
+        Alias, Deprecated, Description, Env, Example, Kind, Name, Opt, Pos, Required,
+    };
+
+    #[test]
+    fn page() {
+        const DESCR: Description<'static> = Description::DEFAULT
+            .name("fetch")
+            .descr("Fetches things")
+            .long_descr("Fetches things from the network.\n.dot and \\backslash")
+            .sections(&["Network"])
+            .positionals(&[Pos {
+                name: "url",
+                descr: "what to fetch",
+                required: Required::Required,
+                ..Pos::DEFAULT
+            }])
+            .options(&[
+                Opt {
+                    short: Some('o'),
+                    long: Some("output"),
+                    kind: Kind::Value {
+                        name: Some("file"),
+                        default: Some("-"),
+                    },
+                    descr: "where to write",
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    long: Some("proxy"),
+                    kind: Kind::Value {
+                        name: Some("url"),
+                        default: None,
+                    },
+                    descr: "use the proxy",
+                    long_descr: Some("use the proxy for all requests"),
+                    section: Some("Network"),
+                    aliases: &[Alias {
+                        name: Name::Long("via"),
+                        visible: true,
+                    }],
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    long: Some("offline"),
+                    descr: "don't use the network",
+                    section: Some("Network"),
+                    deprecated: Some(Deprecated { replacement: None }),
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    long: Some("debug"),
+                    hidden: true,
+                    ..Opt::DEFAULT
+                },
+            ])
+            .examples(&[Example {
+                command: "fetch -o - example.com",
+                descr: "prints the page",
+            }])
+            .env(&[Env {
+                name: "FETCH_PROXY",
+                descr: "proxy used by default",
+                default: Some("none"),
+            }])
+            .after_help("See also curl(1).");
+
+        let mut page = String::new();
+        let header = Page {
+            date: Some("2024-01-31"),
+            ..Page::DEFAULT
+        };
+        render(&DESCR, &header, &mut page).unwrap();
+        assert_eq!(
+            page,
+            r#".TH "FETCH" "1" "2024\-01\-31"
+.SH NAME
+fetch \- Fetches things
+.SH SYNOPSIS
+fetch [options] [Network options] <url>
+.SH DESCRIPTION
+Fetches things from the network.
+.br
+\&.dot and \ebackslash
+.SH ARGUMENTS
+.TP
+\fIurl\fR
+what to fetch
+.SH OPTIONS
+.TP
+\fB\-o\fR, \fB\-\-output\fR \fI<file>\fR
+where to write [default: \-]
+.SH NETWORK
+.TP
+\fB\-\-proxy\fR \fI<url>\fR
+use the proxy for all requests [aliases: \-\-via]
+.TP
+\fB\-\-offline\fR
+don't use the network [deprecated]
+.SH EXAMPLES
+.TP
+\fBfetch \-o \- example.com\fR
+prints the page
+.SH ENVIRONMENT
+.TP
+\fBFETCH_PROXY\fR
+proxy used by default [default: none]
+.SH NOTES
+See also curl(1).
+"#
+        );
+
+        let mut page = String::new();
+        render(&Description::Raw(".raw\n"), &Page::DEFAULT, &mut page).unwrap();
+        assert_eq!(page, ".TH \"\" \"1\"\n.SH DESCRIPTION\n.nf\n\\&.raw\n.fi\n");
+    }
+
+    #[test]
+    fn markup() {
+        const DESCR: Description<'static> = Description::DEFAULT
+            .name("fetch")
+            .descr("Fetches `http` things")
+            .long_descr("Fetches *anything*.\n\nModes:\n- `fast`\n- safe\nDone.")
+            .options(&[Opt {
+                long: Some("mode"),
+                descr: "one of:\n- `fast`\n\n- *safe*\n\nsee above",
+                ..Opt::DEFAULT
+            }]);
+
+        let mut page = String::new();
+        render(&DESCR, &Page::DEFAULT, &mut page).unwrap();
+        assert_eq!(
+            page,
+            r#".TH "FETCH" "1"
+.SH NAME
+fetch \- Fetches http things
+.SH SYNOPSIS
+fetch [options]
+.SH DESCRIPTION
+Fetches \fIanything\fR.
+.PP
+Modes:
+.IP \(bu 2
+\fBfast\fR
+.IP \(bu 2
+safe
+.PP
+Done.
+.SH OPTIONS
+.TP
+\fB\-\-mode\fR
+one of:
+.RS
+.IP \(bu 2
+\fBfast\fR
+.IP \(bu 2
+\fIsafe\fR
+.RE
+.IP
+see above
+"#
+        );
+    }
+}
@@ -0,0 +1,513 @@
+//! Markdown documentation generated from [typed descriptions](Description::Typed), i.e. for a
+//! README or a documentation site.
+//!
+//! ```
+//! use vvvv::{
+//!     help::{Description, Opt},
+//!     markdown,
+//! };
+//!
+//! let descr = Description::DEFAULT
+//!     .name("cp")
+//!     .descr("Copies files")
+//!     .options(&[Opt {
+//!         short: Some('v'),
+//!         descr: "explain what is being done",
+//!         ..Opt::DEFAULT
+//!     }]);
+//!
+//! let mut doc = String::new();
+//! markdown::render(&descr, &mut doc).unwrap();
+//! assert_eq!(
+//!     doc,
+//!     "# cp\n\nCopies files\n\n## Usage\n\n```text\ncp [options]\n```\n\n## Options\n\n- `-v`: explain what is being done\n",
+//! );
+//! ```
+//!
+//! The document has the same parts as the [long help](crate::help::Mode::Long), each under a
+//! `##` heading: usage, positional arguments, options, named [sections](Opt::section),
+//! [examples](Description::Typed::examples) and [environment variables](Description::Typed::env).
+//! The [text after the help](Description::Typed::after_help) ends the document. Hidden options
+//! are left out.
+
+use std::fmt;
+
+use crate::{
+    help::{self, Description, Env, Example, Kind, Opt, Pos},
+    markup::{self, Line, Span},
+};
+
+/// Renders the Markdown document of `descr` to `out`.
+///
+/// [`Description::Raw`] is written as is in a code block.
+pub fn render(descr: &Description<'_>, out: &mut dyn fmt::Write) -> fmt::Result {
+    let (name, descr_, long_descr, sections, positionals, options, examples, env, after_help) =
+        match descr {
+            Description::None => return Ok(()),
+            Description::Raw(text) => return code_block(out, "text", text),
+            Description::Typed {
+                name,
+                descr,
+                long_descr,
+                sections,
+                positionals,
+                options,
+                examples,
+                env,
+                after_help,
+                ..
+            } => (
+                name,
+                descr,
+                long_descr,
+                sections,
+                positionals,
+                options,
+                examples,
+                env,
+                after_help,
+            ),
+        };
+
+    let mut blocks = Blocks { out, first: true };
+
+    if !name.is_empty() {
+        blocks.start()?;
+        writeln!(blocks.out, "# {}", escape(name, true))?;
+    }
+
+    let descr_ = long_descr.unwrap_or(descr_);
+    if !descr_.is_empty() {
+        blocks.start()?;
+        text(blocks.out, descr_, "")?;
+    }
+
+    let usage = descr.usage_line();
+    if !usage.is_empty() {
+        blocks.heading("Usage")?;
+        blocks.start()?;
+        code_block(blocks.out, "text", &usage)?;
+    }
+
+    let options: Vec<_> = options.iter().filter(|opt| !opt.hidden).copied().collect();
+    let positionals_in = |section| -> Vec<_> {
+        positionals
+            .iter()
+            .filter(|pos| pos.section == section)
+            .copied()
+            .collect()
+    };
+    let options_in = |section| -> Vec<_> {
+        options
+            .iter()
+            .filter(|opt| opt.section == section)
+            .copied()
+            .collect()
+    };
+
+    blocks.section("Arguments", &positionals_in(None), &[])?;
+    blocks.section("Options", &[], &options_in(None))?;
+    for name in help::section_order(sections, positionals, &options) {
+        let section = Some(name);
+        blocks.section(name, &positionals_in(section), &options_in(section))?;
+    }
+
+    if !examples.is_empty() {
+        blocks.heading("Examples")?;
+        for example in *examples {
+            blocks.example(example)?;
+        }
+    }
+
+    if !env.is_empty() {
+        blocks.heading("Environment")?;
+        blocks.start()?;
+        for env in *env {
+            env_item(blocks.out, env)?;
+        }
+    }
+
+    if let Some(after_help) = after_help {
+        blocks.start()?;
+        text(blocks.out, after_help, "")?;
+    }
+
+    Ok(())
+}
+
+/// Writer of blocks separated by empty lines.
+struct Blocks<'o> {
+    out: &'o mut dyn fmt::Write,
+    first: bool,
+}
+
+impl Blocks<'_> {
+    /// Separates the next block from the previous one.
+    fn start(&mut self) -> fmt::Result {
+        if !self.first {
+            self.out.write_char('\n')?;
+        }
+        self.first = false;
+        Ok(())
+    }
+
+    fn heading(&mut self, heading: &str) -> fmt::Result {
+        self.start()?;
+        writeln!(self.out, "## {}", escape(heading, true))
+    }
+
+    /// Writes a heading followed by a list of positional arguments and options, nothing if there
+    /// are neither.
+    fn section(
+        &mut self,
+        heading: &str,
+        positionals: &[Pos<'_>],
+        options: &[Opt<'_>],
+    ) -> fmt::Result {
+        if positionals.is_empty() && options.is_empty() {
+            return Ok(());
+        }
+
+        self.heading(heading)?;
+        self.start()?;
+        for pos in positionals {
+            write!(self.out, "- {}", code(pos.name))?;
+            item_text(self.out, pos.long_descr.unwrap_or(pos.descr))?;
+        }
+
+        for opt in options {
+            self.out.write_str("- ")?;
+            option_name(self.out, opt)?;
+            item_text(self.out, &opt.long_text())?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the command of an example in a code block followed by its explanation.
+    fn example(&mut self, example: &Example<'_>) -> fmt::Result {
+        self.start()?;
+        code_block(self.out, "sh", example.command)?;
+        if !example.descr.is_empty() {
+            self.start()?;
+            text(self.out, example.descr, "")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes names of an option and its value placeholder, i.e. `` `-o`, `--output <file>` ``.
+fn option_name(out: &mut dyn fmt::Write, opt: &Opt<'_>) -> fmt::Result {
+    let mut names: Vec<_> = opt
+        .short
+        .map(help::Name::Short)
+        .into_iter()
+        .chain(opt.long.map(help::Name::Long))
+        .map(|name| name.to_string())
+        .collect();
+
+    if let (Kind::Value { name, .. }, Some(last)) = (opt.kind, names.last_mut()) {
+        last.push_str(&format!(" <{}>", name.unwrap_or("val")));
+    }
+
+    for (i, name) in names.iter().enumerate() {
+        let sep = if i == 0 { "" } else { ", " };
+        write!(out, "{}{}", sep, code(name))?;
+    }
+
+    Ok(())
+}
+
+/// Writes a list item of an environment variable.
+fn env_item(out: &mut dyn fmt::Write, env: &Env<'_>) -> fmt::Result {
+    write!(out, "- {}", code(env.name))?;
+    match env.default {
+        Some(def) => item_text(out, &format!("{} [default: {}]", env.descr, def)),
+        None => item_text(out, env.descr),
+    }
+}
+
+/// Writes the description of a list item after its name, ending with a newline.
+fn item_text(out: &mut dyn fmt::Write, descr: &str) -> fmt::Result {
+    match markup::lines(descr).next() {
+        Some(Line::Bullet(_)) => out.write_str(":\n  ")?,
+        Some(Line::Text(_)) => out.write_str(": ")?,
+        _ => return out.write_char('\n'),
+    }
+
+    text(out, descr, "  ")
+}
+
+/// Writes `text` with [markup](Opt::descr), indenting lines after the first by `indent`,
+/// ending with a newline.
+///
+/// Lines of a paragraph end with hard line breaks like in the help, bullets become a list.
+fn text(out: &mut dyn fmt::Write, text: &str, indent: &str) -> fmt::Result {
+    let mut prev = None;
+    for line in markup::lines(text.trim_end_matches('\n')) {
+        match (prev, line) {
+            (None, Line::Blank) | (Some(Line::Blank), Line::Blank) => continue,
+            (Some(Line::Text(_)), Line::Text(_)) => out.write_str("\\\n")?,
+            // a line right after a bullet would continue it
+            (Some(Line::Bullet(_)), Line::Text(_)) => out.write_str("\n\n")?,
+            (Some(_), _) => out.write_char('\n')?,
+            (None, _) => {}
+        }
+
+        match line {
+            Line::Blank => {}
+            Line::Bullet(bullet) => {
+                if prev.is_some() {
+                    out.write_str(indent)?;
+                }
+                out.write_str("- ")?;
+                inline(out, bullet)?;
+            }
+            Line::Text(line) => {
+                if prev.is_some() {
+                    out.write_str(indent)?;
+                }
+                inline(out, line)?;
+            }
+        }
+        prev = Some(line);
+    }
+
+    out.write_char('\n')
+}
+
+/// Writes a line with code spans and emphasis, escaping everything else.
+fn inline(out: &mut dyn fmt::Write, line: &str) -> fmt::Result {
+    for (i, span) in markup::spans(line.trim_start()).into_iter().enumerate() {
+        match span {
+            Span::Text(text) => out.write_str(&escape(text, i == 0))?,
+            Span::Code(text) => out.write_str(&code(text))?,
+            Span::Emphasis(text) => write!(out, "*{}*", escape(text, false))?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a fenced code block, with a fence longer than any backtick run in `text`.
+fn code_block(out: &mut dyn fmt::Write, info: &str, text: &str) -> fmt::Result {
+    let fence = "`".repeat(longest_run(text, '`').max(2) + 1);
+    writeln!(out, "{}{}", fence, info)?;
+    for line in text.lines() {
+        writeln!(out, "{}", line)?;
+    }
+    writeln!(out, "{}", fence)
+}
+
+/// Returns a code span of `text`, with more backticks than any backtick run in `text`.
+fn code(text: &str) -> String {
+    let ticks = "`".repeat(longest_run(text, '`') + 1);
+    match text.starts_with('`') || text.ends_with('`') {
+        true => format!("{} {} {}", ticks, text, ticks),
+        false => format!("{}{}{}", ticks, text, ticks),
+    }
+}
+
+fn longest_run(text: &str, ch: char) -> usize {
+    text.split(|c| c != ch).map(str::len).max().unwrap_or(0)
+}
+
+/// Escapes characters which Markdown would interpret, including block markers if `text` starts
+/// a line.
+fn escape(text: &str, line_start: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    // block markers: headings, quotes, lists, thematic breaks and setext underlines
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let (start, rest) = text.split_at(digits);
+    let block = match digits {
+        0 => rest.starts_with(['#', '>', '-', '+', '=']),
+        _ => rest.starts_with(['.', ')']),
+    };
+    out.push_str(start);
+    if line_start && block {
+        out.push('\\');
+    }
+
+    for ch in rest.chars() {
+        if let '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '&' | '~' | '|' = ch {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, render};
+    use crate::help::{
+        Alias, Deprecated, Description, Env, Example, Kind, Name, Opt, Pos, Required,
+    };
+
+    #[test]
+    fn document() {
+        const DESCR: Description<'static> = Description::DEFAULT
+            .name("fetch")
+            .descr("Fetches things")
+            .long_descr("Fetches things from the network.\nUse with care.")
+            .sections(&["Network"])
+            .positionals(&[Pos {
+                name: "url",
+                descr: "what to fetch",
+                required: Required::Required,
+                ..Pos::DEFAULT
+            }])
+            .options(&[
+                Opt {
+                    short: Some('o'),
+                    long: Some("output"),
+                    kind: Kind::Value {
+                        name: Some("file"),
+                        default: Some("-"),
+                    },
+                    descr: "where to write",
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    long: Some("proxy"),
+                    kind: Kind::Value {
+                        name: Some("url"),
+                        default: None,
+                    },
+                    descr: "use the proxy",
+                    long_descr: Some("use the proxy\nfor all requests"),
+                    section: Some("Network"),
+                    aliases: &[Alias {
+                        name: Name::Long("via"),
+                        visible: true,
+                    }],
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    long: Some("offline"),
+                    section: Some("Network"),
+                    deprecated: Some(Deprecated { replacement: None }),
+                    ..Opt::DEFAULT
+                },
+                Opt {
+                    long: Some("debug"),
+                    hidden: true,
+                    ..Opt::DEFAULT
+                },
+            ])
+            .examples(&[Example {
+                command: "fetch -o - example.com",
+                descr: "prints the page",
+            }])
+            .env(&[Env {
+                name: "FETCH_PROXY",
+                descr: "proxy used by default",
+                default: Some("none"),
+            }])
+            .after_help("See also curl(1).");
+
+        let mut doc = String::new();
+        render(&DESCR, &mut doc).unwrap();
+        assert_eq!(
+            doc,
+            r#"# fetch
+
+Fetches things from the network.\
+Use with care.
+
+## Usage
+
+```text
+fetch [options] [Network options] <url>
+```
+
+## Arguments
+
+- `url`: what to fetch
+
+## Options
+
+- `-o`, `--output <file>`: where to write \[default: -\]
+
+## Network
+
+- `--proxy <url>`: use the proxy\
+  for all requests \[aliases: --via\]
+- `--offline`: \[deprecated\]
+
+## Examples
+
+```sh
+fetch -o - example.com
+```
+
+prints the page
+
+## Environment
+
+- `FETCH_PROXY`: proxy used by default \[default: none\]
+
+See also curl(1).
+"#
+        );
+
+        let mut doc = String::new();
+        render(&Description::Raw("Usage: x\n"), &mut doc).unwrap();
+        assert_eq!(doc, "```text\nUsage: x\n```\n");
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape("# not a heading", true), "\\# not a heading");
+        assert_eq!(escape("- not a bullet", true), "\\- not a bullet");
+        assert_eq!(escape("1. not a list", true), "1\\. not a list");
+        assert_eq!(escape("2024 was a year", true), "2024 was a year");
+        assert_eq!(
+            escape("a_b *c* <d> [e]", true),
+            "a\\_b \\*c\\* \\<d\\> \\[e\\]"
+        );
+    }
+
+    #[test]
+    fn markup() {
+        const DESCR: Description<'static> = Description::DEFAULT
+            .descr("Fetches *anything*.\n\nModes:\n- `fast`\n- safe\nDone.")
+            .options(&[Opt {
+                long: Some("mode"),
+                descr: "- `fast`\n\n- *safe*, 2 * 3\nsee above",
+                ..Opt::DEFAULT
+            }]);
+
+        let mut doc = String::new();
+        render(&DESCR, &mut doc).unwrap();
+        assert_eq!(
+            doc,
+            r#"Fetches *anything*.
+
+Modes:
+- `fast`
+- safe
+
+Done.
+
+## Usage
+
+```text
+[options]
+```
+
+## Options
+
+- `--mode`:
+  - `fast`
+
+  - *safe*, 2 \* 3
+
+  see above
+"#
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Lightweight markup of descriptions, see [`Opt::descr`](crate::help::Opt::descr) for the
+//! syntax.
+//!
+//! Markers which are not closed on the same line (and `*` followed or preceded by a space, i.e.
+//! `2 * 3`) are rendered as is. The terminal renderer, [man pages](crate::man) and
+//! [Markdown](crate::markdown) share the parsing, so that they interpret markup the same way.
+
+use std::fmt;
+
+use crate::{help::Settings, wrap::Wrap};
+
+/// Line of a text with markup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Line<'t> {
+    /// Empty line, which separates paragraphs.
+    Blank,
+    /// Line starting with `- `, without the marker.
+    Bullet(&'t str),
+    Text(&'t str),
+}
+
+/// Part of a line with markup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Span<'t> {
+    Text(&'t str),
+    /// Text between backticks, without them.
+    Code(&'t str),
+    /// Text between asterisks, without them.
+    Emphasis(&'t str),
+}
+
+/// Splits `text` into lines.
+pub(crate) fn lines(text: &str) -> impl Iterator<Item = Line<'_>> {
+    text.split('\n').map(|line| match line {
+        "" => Line::Blank,
+        line => match line.strip_prefix("- ") {
+            Some(bullet) => Line::Bullet(bullet),
+            None => Line::Text(line),
+        },
+    })
+}
+
+/// Splits a line into code spans, emphasis and the text between them.
+pub(crate) fn spans(line: &str) -> Vec<Span<'_>> {
+    let mut spans = Vec::new();
+    let mut text = 0;
+    let mut rest = 0;
+
+    while let Some(i) = line[rest..].find(['`', '*']).map(|i| rest + i) {
+        let marker = if line[i..].starts_with('`') { '`' } else { '*' };
+        let after = &line[i + 1..];
+        let end = after.find(marker).filter(|&end| {
+            end != 0 && (marker == '`' || !(after.starts_with(' ') || after[..end].ends_with(' ')))
+        });
+
+        let end = match end {
+            Some(end) => end,
+            None => {
+                rest = i + 1;
+                continue;
+            }
+        };
+
+        if text != i {
+            spans.push(Span::Text(&line[text..i]));
+        }
+        spans.push(match marker {
+            '`' => Span::Code(&after[..end]),
+            _ => Span::Emphasis(&after[..end]),
+        });
+        rest = i + end + 2;
+        text = rest;
+    }
+
+    if text != line.len() {
+        spans.push(Span::Text(&line[text..]));
+    }
+
+    spans
+}
+
+/// Pushes `text` with markup to `wrap`.
+pub(crate) fn push(wrap: &mut Wrap<'_>, text: &str, settings: &Settings) -> fmt::Result {
+    for (i, line) in lines(text).enumerate() {
+        if i != 0 {
+            wrap.push("\n")?;
+        }
+
+        match line {
+            Line::Blank => {}
+            Line::Bullet(bullet) => {
+                wrap.hang(2);
+                wrap.push("- ")?;
+                inline(wrap, bullet, settings)?;
+            }
+            Line::Text(line) => inline(wrap, line, settings)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Pushes a single line with code spans and emphasis.
+fn inline(wrap: &mut Wrap<'_>, line: &str, settings: &Settings) -> fmt::Result {
+    for span in spans(line) {
+        let (text, style) = match span {
+            Span::Text(text) => {
+                wrap.push(text)?;
+                continue;
+            }
+            Span::Code(code) if !settings.styled => {
+                for chunk in &["`", code, "`"] {
+                    wrap.push(chunk)?;
+                }
+                continue;
+            }
+            Span::Code(code) => (code, settings.theme.code),
+            Span::Emphasis(text) => (text, settings.theme.emphasis),
+        };
+
+        let (on, off) = settings.paint(style);
+        for chunk in &[on, text, off] {
+            wrap.push(chunk)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Line, Span};
+    use crate::{help::Settings, wrap::Wrap};
+
+    fn render(text: &str, limit: usize, styled: bool) -> String {
+        let settings = Settings {
+            styled,
+            ..Settings::default()
+        };
+
+        let mut out = String::new();
+        let mut wrap = Wrap::new(&mut out, limit, 4, 4);
+        super::push(&mut wrap, text, &settings).unwrap();
+        wrap.finish().unwrap();
+        out
+    }
+
+    #[test]
+    fn bullets() {
+        assert_eq!(
+            render(
+                "modes:\n- fast skips all checks\n- safe\n\nnotes",
+                20,
+                false
+            ),
+            "modes:\n    - fast skips all\n      checks\n    - safe\n\n    notes"
+        );
+    }
+
+    #[test]
+    fn inline() {
+        assert_eq!(
+            render("use `--color` *only* if 2 * 3 = 6*", 100, false),
+            "use `--color` only if 2 * 3 = 6*"
+        );
+        assert_eq!(
+            render("use `--color` *only*", 100, true),
+            "use \x1b[1m--color\x1b[0m \x1b[3monly\x1b[0m"
+        );
+        assert_eq!(
+            render("`unclosed and *open", 100, true),
+            "`unclosed and *open"
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            super::lines("text\n- bullet\n\n-no bullet").collect::<Vec<_>>(),
+            [
+                Line::Text("text"),
+                Line::Bullet("bullet"),
+                Line::Blank,
+                Line::Text("-no bullet"),
+            ]
+        );
+        assert_eq!(
+            super::spans("use `--color` *only* if 2 * 3 = 6*"),
+            [
+                Span::Text("use "),
+                Span::Code("--color"),
+                Span::Text(" "),
+                Span::Emphasis("only"),
+                Span::Text(" if 2 * 3 = 6*"),
+            ]
+        );
+        assert_eq!(
+            super::spans("``*`*"),
+            [Span::Text("`"), Span::Code("*"), Span::Text("*")]
+        );
+    }
+}
@@ -0,0 +1,419 @@
+//! Response files, i.e. `@args.txt` arguments which are replaced with arguments read from
+//! `args.txt`, like in rustc, javac or gcc.
+//!
+//! Response files are expanded before [parsing](crate::Token::parse), so they work with any
+//! [`FromArgs`](crate::FromArgs) type:
+//!
+//! ```no_run
+//! use std::env;
+//!
+//! use vvvv::response;
+//!
+//! let args = response::expand(env::args().skip(1)).unwrap_or_else(|err| {
+//!     eprintln!("{}", err);
+//!     std::process::exit(2)
+//! });
+//!
+//! for token in vvvv::Token::parse(args.iter().map(String::as_str)) {
+//!     println!("{}", token);
+//! }
+//! ```
+//!
+//! ## Rules
+//!
+//! - `@path` is replaced with arguments from the file at `path`, relative paths are relative to
+//!   the current directory (also in nested response files).
+//! - `@@arg` is the literal argument `@arg`, `@` alone is kept as is.
+//! - Arguments in a file are separated by whitespace (including newlines). `'single'` quotes
+//!   keep everything literally, in `"double"` quotes `\"` and `\\` are escaped, outside of quotes
+//!   `\` escapes any character.
+//! - Arguments from a file are expanded again, so response files may be nested up to
+//!   [`Expander::max_depth`] levels. A file which includes itself is an error.
+
+use std::{
+    error,
+    fmt::{self, Display},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Expands response files in `args` with the default [`Expander`].
+pub fn expand<I>(args: I) -> Result<Vec<String>, Error>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    Expander::new().expand(args)
+}
+
+/// Response file expansion with custom settings, see the [module docs](self) for the rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Expander {
+    max_depth: usize,
+}
+
+impl Expander {
+    /// Expander which allows up to 16 levels of nested response files.
+    pub const fn new() -> Self {
+        Self { max_depth: 16 }
+    }
+
+    /// Sets how deep response files may be nested, `1` means that response files can't include
+    /// other response files.
+    pub const fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Expands response files in `args`.
+    pub fn expand<I>(&self, args: I) -> Result<Vec<String>, Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut out = Vec::new();
+        let mut stack = Vec::new();
+
+        for arg in args {
+            self.expand_into(arg.as_ref(), &mut out, &mut stack)?;
+        }
+
+        Ok(out)
+    }
+
+    /// Expands a single `arg` into `out`, `stack` holds files which are being expanded (as
+    /// canonical paths and as they were written).
+    fn expand_into(
+        &self,
+        arg: &str,
+        out: &mut Vec<String>,
+        stack: &mut Vec<(PathBuf, PathBuf)>,
+    ) -> Result<(), Error> {
+        let path = match arg.strip_prefix('@') {
+            Some(escaped) if escaped.starts_with('@') => {
+                out.push(escaped.to_owned());
+                return Ok(());
+            }
+            Some(path) if !path.is_empty() => Path::new(path),
+            _ => {
+                out.push(arg.to_owned());
+                return Ok(());
+            }
+        };
+
+        let included_from = || stack.iter().map(|(_, path)| path.clone()).collect();
+        let io_err = |err| Error::Io {
+            path: path.to_owned(),
+            included_from: included_from(),
+            err,
+        };
+        let canonical = fs::canonicalize(path).map_err(io_err)?;
+        if stack.iter().any(|(c, _)| *c == canonical) {
+            return Err(Error::Cycle {
+                path: path.to_owned(),
+                included_from: included_from(),
+            });
+        }
+        if stack.len() >= self.max_depth {
+            return Err(Error::TooDeep {
+                path: path.to_owned(),
+                limit: self.max_depth,
+            });
+        }
+
+        let text = fs::read_to_string(&canonical).map_err(io_err)?;
+        let args = split(&text).ok_or_else(|| Error::UnterminatedQuote {
+            path: path.to_owned(),
+            included_from: included_from(),
+        })?;
+
+        stack.push((canonical, path.to_owned()));
+        for arg in &args {
+            self.expand_into(arg, out, stack)?;
+        }
+        stack.pop();
+
+        Ok(())
+    }
+}
+
+impl Default for Expander {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Splits contents of a response file into arguments.
+///
+/// Returns `None` if a quote is not terminated.
+fn split(text: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    // `None` between arguments, so `''` is an empty argument
+    let mut arg: Option<String> = None;
+    let mut chars = text.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            ch if ch.is_whitespace() => args.extend(arg.take()),
+            '\'' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        ch => arg.push(ch),
+                    }
+                }
+            }
+            '"' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            ch @ ('"' | '\\') => arg.push(ch),
+                            ch => {
+                                arg.push('\\');
+                                arg.push(ch);
+                            }
+                        },
+                        ch => arg.push(ch),
+                    }
+                }
+            }
+            '\\' => {
+                let arg = arg.get_or_insert_with(String::new);
+                arg.push(chars.next().unwrap_or('\\'));
+            }
+            ch => arg.get_or_insert_with(String::new).push(ch),
+        }
+    }
+
+    args.extend(arg);
+    Some(args)
+}
+
+/// Error occured when expanding response files.
+#[derive(Debug)]
+pub enum Error {
+    /// Response file can't be read.
+    ///
+    /// `included_from` lists response files which (transitively) include `path`, outermost
+    /// first. It's empty if `path` was given directly.
+    Io {
+        path: PathBuf,
+        included_from: Vec<PathBuf>,
+        err: io::Error,
+    },
+    /// Response file includes itself, directly or through other response files.
+    ///
+    /// `included_from` is the same as for [`Error::Io`], so it shows the cycle.
+    Cycle {
+        path: PathBuf,
+        included_from: Vec<PathBuf>,
+    },
+    /// Response files are nested deeper than [`Expander::max_depth`].
+    TooDeep { path: PathBuf, limit: usize },
+    /// Response file has a quote which is not terminated.
+    ///
+    /// `included_from` is the same as for [`Error::Io`].
+    UnterminatedQuote {
+        path: PathBuf,
+        included_from: Vec<PathBuf>,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io {
+                path,
+                included_from,
+                err,
+            } => {
+                write!(f, "Can't read response file `{}`", path.display())?;
+                write_included_from(f, included_from)?;
+                write!(f, ": {}", err)
+            }
+            Error::Cycle {
+                path,
+                included_from,
+            } => {
+                write!(f, "Response file `{}` includes itself", path.display())?;
+                write_included_from(f, included_from)
+            }
+            Error::TooDeep { path, limit } => write!(
+                f,
+                "Response file `{}` is nested deeper than {} levels",
+                path.display(),
+                limit
+            ),
+            Error::UnterminatedQuote {
+                path,
+                included_from,
+            } => {
+                write!(
+                    f,
+                    "Unterminated quote in response file `{}`",
+                    path.display()
+                )?;
+                write_included_from(f, included_from)
+            }
+        }
+    }
+}
+
+/// Writes ` (included from `a` -> `b`)`, if `chain` is not empty.
+fn write_included_from(f: &mut fmt::Formatter<'_>, chain: &[PathBuf]) -> fmt::Result {
+    for (i, path) in chain.iter().enumerate() {
+        let sep = if i == 0 { " (included from " } else { " -> " };
+        write!(f, "{}`{}`", sep, path.display())?;
+    }
+
+    if !chain.is_empty() {
+        f.write_str(")")?;
+    }
+
+    Ok(())
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        path::{Path, PathBuf},
+        process,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::{split, Error, Expander};
+
+    /// Temporary directory which is removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        /// Creates an empty directory, unique to this process and call.
+        fn new() -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let dir = env::temp_dir().join(format!("vvvv-response-{}-{}", process::id(), n));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn join(&self, name: &str) -> PathBuf {
+            self.0.join(name)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn at(path: &Path) -> String {
+        format!("@{}", path.display())
+    }
+
+    #[test]
+    fn splitting() {
+        assert_eq!(
+            split("-v  --out 'a b'\n\"c \\\"d\\\" \\e\" f\\ g '' \\").unwrap(),
+            ["-v", "--out", "a b", "c \"d\" \\e", "f g", "", "\\"]
+        );
+        assert_eq!(split(" \r\n\t\u{a0}").unwrap(), Vec::<String>::new());
+        assert_eq!(split("'unterminated"), None);
+        assert_eq!(split("\"unterminated \\\""), None);
+    }
+
+    #[test]
+    fn nested() {
+        let dir = TempDir::new();
+        let inner = dir.join("inner.txt");
+        let outer = dir.join("outer.txt");
+        fs::write(&inner, "-x 'y z'").unwrap();
+        fs::write(&outer, format!("-a\n{}\n@@literal", at(&inner))).unwrap();
+
+        assert_eq!(
+            super::expand(&[
+                "first".to_owned(),
+                at(&outer),
+                "@".to_owned(),
+                "@@b".to_owned()
+            ])
+            .unwrap(),
+            ["first", "-a", "-x", "y z", "@literal", "@", "@b"]
+        );
+        assert!(matches!(
+            Expander::new().max_depth(1).expand(&[at(&outer)]),
+            Err(Error::TooDeep { limit: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        let dir = TempDir::new();
+        let a = dir.join("a.txt");
+        let b = dir.join("b.txt");
+        fs::write(&a, at(&b)).unwrap();
+        fs::write(&b, format!("-v {}", at(&a))).unwrap();
+
+        let err = super::expand(&[at(&a)]).unwrap_err();
+        assert!(matches!(&err, Error::Cycle { path, included_from }
+            if *path == a && *included_from == [a.clone(), b.clone()]));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Response file `{}` includes itself (included from `{}` -> `{}`)",
+                a.display(),
+                a.display(),
+                b.display()
+            )
+        );
+
+        let missing = dir.join("missing.txt");
+        let err = super::expand(&[at(&missing)]).unwrap_err();
+        assert!(matches!(&err, Error::Io { path, included_from, .. }
+            if *path == missing && included_from.is_empty()));
+        assert!(err.to_string().contains("missing.txt"));
+
+        let outer = dir.join("outer.txt");
+        let inner = dir.join("inner.txt");
+        fs::write(&outer, at(&inner)).unwrap();
+        fs::write(&inner, format!("-v {}", at(&missing))).unwrap();
+        let err = super::expand(&[at(&outer)]).unwrap_err();
+        assert!(matches!(&err, Error::Io { path, included_from, .. }
+            if *path == missing && *included_from == [outer.clone(), inner.clone()]));
+        assert!(err.to_string().starts_with(&format!(
+            "Can't read response file `{}` (included from `{}` -> `{}`): ",
+            missing.display(),
+            outer.display(),
+            inner.display()
+        )));
+
+        let quote = dir.join("quote.txt");
+        fs::write(&quote, "'oops").unwrap();
+        fs::write(&outer, at(&quote)).unwrap();
+        match super::expand(&[at(&outer)]) {
+            Err(Error::UnterminatedQuote {
+                path,
+                included_from,
+            }) => {
+                assert_eq!(path, quote);
+                assert_eq!(included_from, [outer]);
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
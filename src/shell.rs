@@ -0,0 +1,208 @@
+//! Splitting a command line string into arguments and quoting arguments back, following POSIX
+//! shell quoting rules.
+//!
+//! ```
+//! use vvvv::shell;
+//!
+//! let args = shell::split(r#"build --target "x86 64" -o 'a b'"#).unwrap();
+//! assert_eq!(args, ["build", "--target", "x86 64", "-o", "a b"]);
+//! assert_eq!(shell::join(&args), "build --target 'x86 64' -o 'a b'");
+//!
+//! let tokens: Vec<_> = vvvv::Token::parse(args.iter().map(String::as_str)).collect();
+//! assert_eq!(tokens[0], vvvv::Token::Positional("build"));
+//! ```
+//!
+//! ## Rules
+//!
+//! - Arguments are separated by unquoted ASCII whitespace.
+//! - `'single'` quotes keep everything literally, a single quote can't appear inside them.
+//! - In `"double"` quotes `\` escapes only `$`, `` ` ``, `"`, `\` and a newline (which is
+//!   removed), it is kept literally before any other character.
+//! - Outside of quotes `\` escapes any character, `\` followed by a newline is removed.
+//! - Nothing is expanded, i.e. `$VAR`, `~`, `*` or `` `cmd` `` are kept as is.
+
+use std::{
+    borrow::Cow,
+    error,
+    fmt::{self, Display},
+};
+
+/// Splits `line` into arguments.
+pub fn split(line: &str) -> Result<Vec<String>, SplitError> {
+    let mut args = Vec::new();
+    // `None` between arguments, so `''` is an empty argument
+    let mut arg: Option<String> = None;
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((offset, ch)) = chars.next() {
+        match ch {
+            ch if ch.is_ascii_whitespace() => args.extend(arg.take()),
+            '\'' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) => break,
+                        Some((_, ch)) => arg.push(ch),
+                        None => {
+                            return Err(SplitError::UnterminatedQuote {
+                                quote: '\'',
+                                offset,
+                            })
+                        }
+                    }
+                }
+            }
+            '"' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.peek() {
+                            Some((_, '\n')) => {
+                                chars.next();
+                            }
+                            Some(&(_, ch @ ('$' | '`' | '"' | '\\'))) => {
+                                chars.next();
+                                arg.push(ch);
+                            }
+                            _ => arg.push('\\'),
+                        },
+                        Some((_, ch)) => arg.push(ch),
+                        None => return Err(SplitError::UnterminatedQuote { quote: '"', offset }),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some((_, '\n')) => {}
+                Some((_, ch)) => arg.get_or_insert_with(String::new).push(ch),
+                None => return Err(SplitError::TrailingBackslash { offset }),
+            },
+            ch => arg.get_or_insert_with(String::new).push(ch),
+        }
+    }
+
+    args.extend(arg);
+    Ok(args)
+}
+
+/// Quotes `arg` so that [`split`] (or a POSIX shell) reads it back as a single argument.
+///
+/// Arguments which don't need quoting are returned as is. Arguments with `=` are always quoted,
+/// so that a shell doesn't take `NAME=value` as a variable assignment.
+pub fn quote(arg: &str) -> Cow<'_, str> {
+    let safe = |ch: char| ch.is_ascii_alphanumeric() || "_-+@%:,./".contains(ch);
+    if !arg.is_empty() && arg.chars().all(safe) {
+        return Cow::Borrowed(arg);
+    }
+
+    let mut out = String::with_capacity(arg.len() + 2);
+    out.push('\'');
+    for ch in arg.chars() {
+        match ch {
+            '\'' => out.push_str("'\\''"),
+            ch => out.push(ch),
+        }
+    }
+    out.push('\'');
+    Cow::Owned(out)
+}
+
+/// [Quotes](quote) every argument and joins them with spaces, this is the reverse of [`split`].
+pub fn join<I>(args: I) -> String
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut out = String::new();
+    for arg in args {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(&quote(arg.as_ref()));
+    }
+    out
+}
+
+/// Error occured when [splitting](split) a command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitError {
+    /// A quote is not terminated. I.e. `'abc`.
+    ///
+    /// `offset` is the byte offset of the opening quote.
+    UnterminatedQuote { quote: char, offset: usize },
+    /// The line ends with a backslash which doesn't escape anything. I.e. `abc\`.
+    TrailingBackslash { offset: usize },
+}
+
+impl Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplitError::UnterminatedQuote { quote, offset } => {
+                write!(f, "Unterminated quote `{}` at offset {}", quote, offset)
+            }
+            SplitError::TrailingBackslash { offset } => {
+                write!(f, "Trailing backslash at offset {}", offset)
+            }
+        }
+    }
+}
+
+impl error::Error for SplitError {}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{join, quote, split, SplitError};
+
+    #[test]
+    fn splitting() {
+        assert_eq!(
+            split("  a\t'b c'd \"e \\\"f\\\" \\g $x\" h\\ i '' \\\nj\n").unwrap(),
+            ["a", "b cd", "e \"f\" \\g $x", "h i", "", "j"]
+        );
+        assert_eq!(split("").unwrap(), Vec::<String>::new());
+        assert_eq!(split("'a\\'").unwrap(), ["a\\"]);
+        assert_eq!(split("\"a\\\nb\"").unwrap(), ["ab"]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            split("ab 'cd"),
+            Err(SplitError::UnterminatedQuote {
+                quote: '\'',
+                offset: 3
+            })
+        );
+        assert_eq!(
+            split("\"ab\\\""),
+            Err(SplitError::UnterminatedQuote {
+                quote: '"',
+                offset: 0
+            })
+        );
+        assert_eq!(
+            split("ab\\"),
+            Err(SplitError::TrailingBackslash { offset: 2 })
+        );
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(quote("--out=a/b.txt"), "'--out=a/b.txt'");
+        assert_eq!(quote("-o"), "-o");
+        assert_eq!(quote(""), "''");
+        assert_eq!(quote("a b"), "'a b'");
+        assert_eq!(quote("it's"), "'it'\\''s'");
+        assert_eq!(join(&["echo", "$HOME", ""]), "echo '$HOME' ''");
+        assert_eq!(join(&["FOO=bar", "cmd"]), "'FOO=bar' cmd");
+    }
+
+    proptest! {
+        #[test]
+        fn roundtrip(args in prop::collection::vec("[a-z '\"\\\\$\n\t表=-]{0,8}", 0..6)) {
+            prop_assert_eq!(split(&join(&args)).unwrap(), args);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Minimal terminal queries used to render help.
+
+use std::{
+    env,
+    ffi::OsStr,
+    io::{self, IsTerminal},
+};
+
+/// Standard output stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Returns `true` if the `stream` is connected to a terminal.
+pub fn is_tty(stream: Stream) -> bool {
+    match stream {
+        Stream::Stdout => io::stdout().is_terminal(),
+        Stream::Stderr => io::stderr().is_terminal(),
+    }
+}
+
+/// Returns width (in columns) of the terminal the `stream` is connected to.
+///
+/// Returns `None` if the `stream` is not a terminal or if the width can't be queried on this
+/// platform.
+#[cfg(unix)]
+pub fn width(stream: Stream) -> Option<usize> {
+    let fd = match stream {
+        Stream::Stdout => libc::STDOUT_FILENO,
+        Stream::Stderr => libc::STDERR_FILENO,
+    };
+
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+
+    // SAFETY: `TIOCGWINSZ` only writes a `winsize` into the provided pointer, which is valid.
+    let res = unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) };
+    match (res, size.ws_col) {
+        (0, cols) if cols > 0 => Some(cols.into()),
+        _ => None,
+    }
+}
+
+/// Returns width (in columns) of the terminal the `stream` is connected to.
+///
+/// Returns `None` if the `stream` is not a terminal or if the width can't be queried on this
+/// platform.
+#[cfg(not(unix))]
+pub fn width(_stream: Stream) -> Option<usize> {
+    None
+}
+
+/// When to use colors and other styles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Always use colors.
+    Always,
+    /// Never use colors.
+    Never,
+    /// Use colors if the output stream is a terminal.
+    ///
+    /// `NO_COLOR` (if set and not empty) disables colors, `CLICOLOR_FORCE` (if set and not `0`)
+    /// enables colors even if the output stream is not a terminal.
+    Auto,
+}
+
+impl ColorChoice {
+    /// Returns `true` if colors should be used for output to the `stream`.
+    pub fn resolve(self, stream: Stream) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => resolve_auto(
+                env::var_os("NO_COLOR").as_deref(),
+                env::var_os("CLICOLOR_FORCE").as_deref(),
+                || is_tty(stream),
+            ),
+        }
+    }
+}
+
+fn resolve_auto(
+    no_color: Option<&OsStr>,
+    clicolor_force: Option<&OsStr>,
+    tty: impl FnOnce() -> bool,
+) -> bool {
+    if no_color.is_some_and(|v| !v.is_empty()) {
+        return false;
+    }
+
+    if clicolor_force.is_some_and(|v| !v.is_empty() && v != "0") {
+        return true;
+    }
+
+    tty()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsStr;
+
+    use super::resolve_auto;
+
+    #[test]
+    fn color_auto() {
+        let s = |s| Some(OsStr::new(s));
+
+        assert!(resolve_auto(None, None, || true));
+        assert!(!resolve_auto(None, None, || false));
+        assert!(!resolve_auto(s("1"), None, || true));
+        assert!(resolve_auto(s(""), None, || true));
+        assert!(resolve_auto(None, s("1"), || false));
+        assert!(!resolve_auto(None, s("0"), || false));
+        assert!(!resolve_auto(s("1"), s("1"), || true));
+    }
+}
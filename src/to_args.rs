@@ -0,0 +1,103 @@
+use std::fmt::Display;
+
+use crate::{
+    help::{Description, Name},
+    OwnToken,
+};
+
+/// Builder of tokens representing a value, see [`ToArgs`](crate::ToArgs).
+///
+/// [`to_args`](crate::to_args()) replaces aliases with the primary name of their option from
+/// [`FromArgs::DESCRIPTION`](crate::FromArgs::DESCRIPTION), so the same value always gives the
+/// same arguments. A short name stays short if the option has one, i.e. `Name::Short('v')` is
+/// written as `-v` even if the option also has a long name.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub(crate) tokens: Vec<OwnToken>,
+}
+
+impl Args {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self { tokens: Vec::new() }
+    }
+
+    /// Writes the flag `name` if it's `set`.
+    pub fn flag(&mut self, name: Name<'_>, set: bool) -> &mut Self {
+        if set {
+            self.option(name, None);
+        }
+        self
+    }
+
+    /// Writes the counter `name` `count` times.
+    pub fn count(&mut self, name: Name<'_>, count: usize) -> &mut Self {
+        for _ in 0..count {
+            self.option(name, None);
+        }
+        self
+    }
+
+    /// Writes the option `name` with the `value`.
+    pub fn value(&mut self, name: Name<'_>, value: impl Display) -> &mut Self {
+        self.option(name, Some(value.to_string().into()));
+        self
+    }
+
+    /// Writes the positional argument `value`.
+    pub fn positional(&mut self, value: impl Display) -> &mut Self {
+        self.tokens
+            .push(OwnToken::Positional(value.to_string().into()));
+        self
+    }
+
+    /// Writes `--`.
+    pub fn dash_dash(&mut self) -> &mut Self {
+        self.tokens.push(OwnToken::DashDash);
+        self
+    }
+
+    /// Replaces names of options from the `descr` with their primary names.
+    pub(crate) fn normalize(&mut self, descr: &Description<'_>) {
+        for token in &mut self.tokens {
+            if !matches!(token, OwnToken::Short { .. } | OwnToken::Long { .. }) {
+                continue;
+            }
+
+            let opt = match descr.find_option(&token.borrow()) {
+                Some(opt) => opt,
+                None => continue,
+            };
+            let name = match (&*token, opt.short, opt.long) {
+                (OwnToken::Short { .. }, Some(short), _) | (_, Some(short), None) => {
+                    Name::Short(short)
+                }
+                (_, _, Some(long)) => Name::Long(long),
+                (_, None, None) => continue,
+            };
+
+            let value = match token {
+                OwnToken::Short { value, .. } | OwnToken::Long { value, .. } => value.take(),
+                _ => continue,
+            };
+            *token = match name {
+                Name::Short(key) => OwnToken::Short { key, value },
+                Name::Long(key) => OwnToken::Long {
+                    key: key.into(),
+                    value,
+                },
+            };
+        }
+    }
+
+    fn option(&mut self, name: Name<'_>, value: Option<Box<str>>) {
+        let token = match name {
+            Name::Short(key) => OwnToken::Short { key, value },
+            Name::Long(key) => OwnToken::Long {
+                key: key.into(),
+                value,
+            },
+        };
+        self.tokens.push(token);
+    }
+}
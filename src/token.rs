@@ -1,6 +1,10 @@
-use std::{fmt::Display, iter::Peekable, str::Chars};
+use std::{env, fmt::Display, iter::Peekable};
 
-use crate::tr::IntoOwned;
+use crate::{
+    help::{Description, Name},
+    tr::IntoOwned,
+    UnparseError,
+};
 
 /// A single token of command line arguments.
 ///
@@ -15,7 +19,18 @@ use crate::tr::IntoOwned;
 /// - `-x -y` parsed as 2 short options `x` and `y`.
 /// - `-x -` is parsed as short option `x` with value `-`.
 /// - Everything after `--` token parsed as a positional.
+/// - Options and positionals can be mixed, unless the [POSIX mode](ParseConfig::posix) is on.
+/// - Negative numbers (`-5`) are parsed as short options, unless [`NegativeNumbers::Values`] is
+///   used. [`FromArgs`](crate::FromArgs) uses [`NegativeNumbers::Auto`] by default, so
+///   `--offset -5` works there.
+/// - Alternative prefixes (`+x`, `-name`, `/flag:value`, `key=value`) are off, see [`Prefix`].
+///
+/// These rules can be changed with [`ParseConfig`], see [`Token::parse_with`].
+///
+/// The enum is `#[non_exhaustive]`: new kinds of options were added before ([`Token::Keyed`])
+/// and may be added again, so matches need a wildcard arm.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum Token<'a> {
     /// Positional argument, i.e. just `something`.
     Positional(&'a str),
@@ -31,6 +46,42 @@ pub enum Token<'a> {
     },
     // Duble dash, i.e. `--`.
     DashDash,
+    /// Option with an alternative prefix, i.e. `+x`, `-name value`, `/flag:value` or no prefix at
+    /// all (`key=value`).
+    ///
+    /// Only produced if the prefix is enabled in the [`ParseConfig`], see [`Prefix`].
+    Keyed {
+        prefix: Prefix,
+        key: &'a str,
+        value: Option<&'a str>,
+    },
+}
+
+/// Prefix of a [`Token::Keyed`] option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Prefix {
+    /// `+key`, i.e. `set +x`, see [`ParseConfig::plus_options`].
+    Plus,
+    /// `-key`, i.e. `find -name value`, see [`ParseConfig::single_dash_long`].
+    SingleDash,
+    /// `/key` or `/key:value`, i.e. `xcopy /exclude:list`, see [`ParseConfig::slash_options`].
+    Slash,
+    /// No prefix, `key=value`, i.e. `dd if=a`, see [`ParseConfig::key_value_words`].
+    Bare,
+    /// No prefix, `keyword value`, i.e. `ip link set dev eth0`, see [`ParseConfig::keywords`].
+    Keyword,
+}
+
+impl Display for Prefix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Prefix::Plus => f.write_str("+"),
+            Prefix::SingleDash => f.write_str("-"),
+            Prefix::Slash => f.write_str("/"),
+            Prefix::Bare | Prefix::Keyword => Ok(()),
+        }
+    }
 }
 
 impl<'a> Token<'a> {
@@ -38,17 +89,659 @@ impl<'a> Token<'a> {
     ///
     /// For parse notes see [`Token`](Token#parsing-notes)
     pub fn parse<I>(args: I) -> Parse<'a, I>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        Self::parse_with(ParseConfig::DEFAULT, args)
+    }
+
+    /// Creates parsing iterator which follows the `config`.
+    ///
+    /// ```
+    /// use vvvv::{ParseConfig, Token, ValuePolicy};
+    ///
+    /// let config = ParseConfig::new()
+    ///     .split_eq(true)
+    ///     .values(ValuePolicy::Attached);
+    /// let args = ["--color=never", "--verbose", "file"];
+    ///
+    /// assert_eq!(
+    ///     Token::parse_with(config, args.iter().copied()).collect::<Vec<_>>(),
+    ///     [
+    ///         Token::Long { key: "color", value: Some("never") },
+    ///         Token::Long { key: "verbose", value: None },
+    ///         Token::Positional("file"),
+    ///     ],
+    /// );
+    /// ```
+    pub fn parse_with<I>(config: ParseConfig, args: I) -> Parse<'a, I>
     where
         I: Iterator<Item = &'a str>,
     {
         Parse {
             args: args.peekable(),
-            shorts: "".chars().peekable(),
+            config,
+            shorts: "",
             pos_only: false,
         }
     }
 }
 
+impl<'a> Token<'a> {
+    /// Turns `tokens` back into arguments which [parse](Token::parse) to exactly the same tokens.
+    ///
+    /// Consecutive short options without values are clustered (`-v -x` becomes `-vx`), other
+    /// tokens are written one by one. Use [`Token::unparse_with`] to unparse for other rules.
+    ///
+    /// A positional argument which looks like an option (`-x`) is written after an added `--`,
+    /// if only positional arguments follow it. The arguments then parse to the same tokens with
+    /// an additional [`Token::DashDash`] before that positional argument.
+    ///
+    /// Option values starting with `-` can't be represented with the default rules: `--out -x`
+    /// parses as two options, so such values are an error. Use a config which
+    /// [splits](ParseConfig::split_eq) at `=` for them, see [`Token::unparse_with`].
+    ///
+    /// ```
+    /// use vvvv::{OwnToken, Token};
+    ///
+    /// let tokens = [
+    ///     OwnToken::Short { key: 'v', value: None },
+    ///     OwnToken::Short { key: 'x', value: None },
+    ///     OwnToken::Positional("file".into()),
+    ///     OwnToken::Long { key: "out".into(), value: Some("a b".into()) },
+    /// ];
+    /// assert_eq!(Token::unparse(&tokens).unwrap(), ["-vx", "file", "--out", "a b"]);
+    ///
+    /// let tokens = [Token::Positional("file"), Token::Positional("-x")];
+    /// assert_eq!(Token::unparse(tokens.iter().copied()).unwrap(), ["file", "--", "-x"]);
+    ///
+    /// // `--out file` is `--out` with the value `file`
+    /// let tokens = [Token::Long { key: "out", value: None }, Token::Positional("file")];
+    /// assert_eq!(Token::unparse(tokens.iter().copied()).unwrap_err().index, 0);
+    /// ```
+    pub fn unparse<I>(tokens: I) -> Result<Vec<String>, UnparseError>
+    where
+        I: IntoIterator,
+        I::Item: Into<Token<'a>>,
+    {
+        Self::unparse_with(ParseConfig::DEFAULT, tokens)
+    }
+
+    /// Turns `tokens` back into arguments which [parse](Token::parse_with) to exactly the same
+    /// tokens under the `config`.
+    ///
+    /// Values which wouldn't be taken from the next argument (or all values with
+    /// [`ValuePolicy::Attached`]) are joined with `=` if the config
+    /// [splits](ParseConfig::split_eq) at `=`, so values starting with `-` survive (`--offset=-x`).
+    ///
+    /// ```
+    /// use vvvv::{ParseConfig, Token};
+    ///
+    /// let config = ParseConfig::new().split_eq(true);
+    /// let tokens = [Token::Short { key: 'k', value: Some("-x") }];
+    ///
+    /// assert!(Token::unparse(tokens.iter().copied()).is_err());
+    /// assert_eq!(Token::unparse_with(config, tokens.iter().copied()).unwrap(), ["-k=-x"]);
+    /// ```
+    pub fn unparse_with<I>(config: ParseConfig, tokens: I) -> Result<Vec<String>, UnparseError>
+    where
+        I: IntoIterator,
+        I::Item: Into<Token<'a>>,
+    {
+        let tokens: Vec<Token<'a>> = tokens.into_iter().map(Into::into).collect();
+        let index = match unparse_checked(config, &tokens) {
+            Ok(args) => return Ok(args),
+            Err(index) => index,
+        };
+
+        // A positional argument which looks like an option survives after `--`, unless an
+        // option follows it
+        let positionals = tokens[index..]
+            .iter()
+            .all(|token| matches!(token, Token::Positional(_)));
+        if positionals && config.dash_dash == DashDashPolicy::EndOfOptions {
+            let mut tokens = tokens.clone();
+            tokens.insert(index, Token::DashDash);
+            if let Ok(args) = unparse_checked(config, &tokens) {
+                return Ok(args);
+            }
+        }
+
+        Err(UnparseError {
+            index,
+            token: tokens[index].into_owned(),
+        })
+    }
+}
+
+/// Writes `tokens` as arguments which parse back to the same tokens, or returns the index of the
+/// first token which doesn't.
+///
+/// Values are joined with `=` only where needed, unless that fails and the config
+/// [splits](ParseConfig::split_eq) at `=`, then all values are joined.
+fn unparse_checked(config: ParseConfig, tokens: &[Token<'_>]) -> Result<Vec<String>, usize> {
+    let args = unparse_args(config, tokens, false);
+    let index = match first_mismatch(config, &args, tokens) {
+        None => return Ok(args),
+        Some(index) => index,
+    };
+
+    if config.split_eq {
+        let args = unparse_args(config, tokens, true);
+        if first_mismatch(config, &args, tokens).is_none() {
+            return Ok(args);
+        }
+    }
+
+    Err(index)
+}
+
+/// Writes `tokens` as arguments, without checking that they parse back to the same tokens.
+///
+/// With `join_all` values are joined with `=` even if they could be separate arguments.
+fn unparse_args(config: ParseConfig, tokens: &[Token<'_>], join_all: bool) -> Vec<String> {
+    // `key_ok` is `false` if the option can't be written without its value
+    let join = |key_ok: bool, value: &str| {
+        config.split_eq && (join_all || !key_ok || needs_eq(config, value))
+    };
+    let mut args: Vec<String> = Vec::new();
+    // The last argument is a cluster of short options which may be extended
+    let mut cluster = false;
+
+    for &token in tokens {
+        match token {
+            Token::Positional(s) => args.push(s.to_owned()),
+            Token::Short { key, value: None } => {
+                let key_ok = !(config.split_eq && key == '=');
+                match args.last_mut() {
+                    Some(arg) if cluster && key_ok => arg.push(key),
+                    _ => args.push(format!("-{}", key)),
+                }
+                cluster = config.split_clusters;
+                continue;
+            }
+            Token::Short {
+                key,
+                value: Some(value),
+            } => {
+                if join(true, value) {
+                    match args.last_mut() {
+                        Some(arg) if cluster && key != '=' => {
+                            arg.push_str(&format!("{}={}", key, value))
+                        }
+                        _ => args.push(format!("-{}={}", key, value)),
+                    }
+                } else {
+                    let attached = format!("-{}{}", key, value);
+                    let number = config.negative_numbers != NegativeNumbers::Options
+                        && is_negative_number(&attached);
+                    if !config.split_clusters && !value.is_empty() && !number {
+                        args.push(attached);
+                    } else {
+                        args.push(format!("-{}", key));
+                        args.push(value.to_owned());
+                    }
+                }
+            }
+            Token::Long { key, value: None } => args.push(format!("--{}", key)),
+            Token::Long {
+                key,
+                value: Some(value),
+            } => {
+                if !key.contains('=') && join(!key.is_empty(), value) {
+                    args.push(format!("--{}={}", key, value));
+                } else {
+                    args.push(format!("--{}", key));
+                    args.push(value.to_owned());
+                }
+            }
+            Token::DashDash => args.push("--".to_owned()),
+            Token::Keyed { prefix, key, value } => match (prefix, value) {
+                (_, None) => args.push(format!("{}{}", prefix, key)),
+                (Prefix::Slash, Some(value)) => args.push(format!("/{}:{}", key, value)),
+                (Prefix::Keyword, Some(value)) => {
+                    args.push(key.to_owned());
+                    args.push(value.to_owned());
+                }
+                (Prefix::SingleDash, Some(value)) if !join(!key.is_empty(), value) => {
+                    args.push(format!("-{}", key));
+                    args.push(value.to_owned());
+                }
+                (_, Some(value)) => args.push(format!("{}{}={}", prefix, key, value)),
+            },
+        }
+        cluster = false;
+    }
+
+    args
+}
+
+/// Returns `true` if the `value` can't be written as a separate argument after its option (or
+/// attached to it without `=`).
+fn needs_eq(config: ParseConfig, value: &str) -> bool {
+    config.values == ValuePolicy::Attached
+        || value.starts_with('=')
+        || next_value(&mut std::iter::once(value).peekable(), &config).is_none()
+}
+
+/// Returns the index of the first token which doesn't survive re-lexing of `args`.
+///
+/// Some sequences can't be represented at all, i.e. `--out` followed by a positional argument.
+fn first_mismatch(config: ParseConfig, args: &[String], tokens: &[Token<'_>]) -> Option<usize> {
+    let relexed: Vec<_> = Token::parse_with(config, args.iter().map(String::as_str)).collect();
+    if relexed == tokens {
+        return None;
+    }
+
+    let index = tokens
+        .iter()
+        .zip(&relexed)
+        .position(|(token, relexed)| token != relexed)
+        .unwrap_or_else(|| relexed.len().min(tokens.len() - 1));
+    Some(index)
+}
+
+/// Lexing rules used by [`Token::parse_with`].
+///
+/// [`ParseConfig::DEFAULT`] follows the [parsing notes](Token#parsing-notes) of [`Token`],
+/// builder methods switch individual rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseConfig {
+    values: ValuePolicy,
+    split_clusters: bool,
+    dash_dash: DashDashPolicy,
+    split_eq: bool,
+    negative_numbers: NegativeNumbers,
+    posix: bool,
+    posixly_correct: bool,
+    plus: bool,
+    single_dash_long: bool,
+    slash: bool,
+    key_value_words: bool,
+    keywords: &'static [&'static str],
+    abbreviations: bool,
+}
+
+impl ParseConfig {
+    /// Rules used by [`Token::parse`].
+    pub const DEFAULT: Self = Self {
+        values: ValuePolicy::Greedy,
+        split_clusters: true,
+        dash_dash: DashDashPolicy::EndOfOptions,
+        split_eq: false,
+        negative_numbers: NegativeNumbers::Options,
+        posix: false,
+        posixly_correct: false,
+        plus: false,
+        single_dash_long: false,
+        slash: false,
+        key_value_words: false,
+        keywords: &[],
+        abbreviations: false,
+    };
+
+    /// Same as [`ParseConfig::DEFAULT`].
+    pub const fn new() -> Self {
+        Self::DEFAULT
+    }
+
+    /// Sets how options take values, [`ValuePolicy::Greedy`] by default.
+    pub const fn values(mut self, values: ValuePolicy) -> Self {
+        self.values = values;
+        self
+    }
+
+    /// Sets whether `-xyz` is split into `-x`, `-y` and `-z` (`true`, default) or is `-x` with
+    /// the value `yz` (`false`).
+    pub const fn split_clusters(mut self, split: bool) -> Self {
+        self.split_clusters = split;
+        self
+    }
+
+    /// Sets how `--` is handled, [`DashDashPolicy::EndOfOptions`] by default.
+    pub const fn dash_dash(mut self, dash_dash: DashDashPolicy) -> Self {
+        self.dash_dash = dash_dash;
+        self
+    }
+
+    /// Sets whether `--key=value` and `-k=value` are split at the first `=` (`false` by default,
+    /// `--key=value` is a long option named `key=value`).
+    pub const fn split_eq(mut self, split: bool) -> Self {
+        self.split_eq = split;
+        self
+    }
+
+    /// Sets how arguments which look like negative numbers (`-1`, `-0.5`) are handled,
+    /// [`NegativeNumbers::Options`] by default.
+    pub const fn negative_numbers(mut self, negative_numbers: NegativeNumbers) -> Self {
+        self.negative_numbers = negative_numbers;
+        self
+    }
+}
+
+impl ParseConfig {
+    /// Resolves [`NegativeNumbers::Auto`] against options of the `descr` and turns the
+    /// [POSIX mode](ParseConfig::posix) on if [asked](ParseConfig::posixly_correct) by the
+    /// environment.
+    ///
+    /// [`from_args`](crate::from_args()) and [`to_args`](crate::to_args()) use the resolved
+    /// [`FromArgs::PARSE_CONFIG`](crate::FromArgs::PARSE_CONFIG).
+    ///
+    /// ```
+    /// use vvvv::{
+    ///     help::{Description, Opt},
+    ///     NegativeNumbers, ParseConfig,
+    /// };
+    ///
+    /// let descr = Description::DEFAULT
+    ///     .name("grep")
+    ///     .options(&[Opt {
+    ///         short: Some('5'),
+    ///         descr: "print 5 lines of context",
+    ///         ..Opt::DEFAULT
+    ///     }]);
+    ///
+    /// let auto = ParseConfig::DEFAULT.negative_numbers(NegativeNumbers::Auto);
+    /// assert_eq!(
+    ///     auto.resolve(&descr),
+    ///     ParseConfig::DEFAULT.negative_numbers(NegativeNumbers::Options),
+    /// );
+    /// assert_eq!(
+    ///     auto.resolve(&Description::None),
+    ///     ParseConfig::DEFAULT.negative_numbers(NegativeNumbers::Values),
+    /// );
+    /// ```
+    pub fn resolve(self, descr: &Description<'_>) -> Self {
+        let config = match env::var_os("POSIXLY_CORRECT") {
+            Some(_) if self.posixly_correct => self.posix(true),
+            _ => self,
+        };
+        if config.negative_numbers != NegativeNumbers::Auto {
+            return config;
+        }
+
+        let options = match descr {
+            Description::Typed { options, .. } => *options,
+            _ => &[],
+        };
+        let digit = options
+            .iter()
+            .any(|opt| ('0'..='9').any(|d| opt.has_name(Name::Short(d))));
+
+        if digit {
+            config.negative_numbers(NegativeNumbers::Options)
+        } else {
+            config.negative_numbers(NegativeNumbers::Values)
+        }
+    }
+}
+
+impl ParseConfig {
+    /// Sets whether the first positional argument ends options, so all following arguments are
+    /// positional (`false` by default).
+    ///
+    /// This is what POSIX requires and what wrapper commands (like `sudo` or `time`) need, so
+    /// `wrapper cmd -v` passes `-v` to `cmd`. Such commands usually also need
+    /// [`ValuePolicy::Attached`], otherwise `wrapper -v cmd` takes `cmd` as the value of `-v`.
+    ///
+    /// ```
+    /// use vvvv::{ParseConfig, Token, ValuePolicy};
+    ///
+    /// let config = ParseConfig::new()
+    ///     .posix(true)
+    ///     .values(ValuePolicy::Attached);
+    /// let args = ["-v", "cmd", "-v"];
+    ///
+    /// assert_eq!(
+    ///     Token::parse_with(config, args.iter().copied()).collect::<Vec<_>>(),
+    ///     [
+    ///         Token::Short { key: 'v', value: None },
+    ///         Token::Positional("cmd"),
+    ///         Token::Positional("-v"),
+    ///     ],
+    /// );
+    /// ```
+    pub const fn posix(mut self, posix: bool) -> Self {
+        self.posix = posix;
+        self
+    }
+
+    /// Sets whether the `POSIXLY_CORRECT` environment variable turns the [POSIX
+    /// mode](ParseConfig::posix) on (`false` by default).
+    ///
+    /// The variable is checked by [`ParseConfig::resolve`], not by the lexer, so
+    /// [`Token::parse_with`] and types which don't opt in are not affected by the environment.
+    pub const fn posixly_correct(mut self, posixly_correct: bool) -> Self {
+        self.posixly_correct = posixly_correct;
+        self
+    }
+}
+
+impl ParseConfig {
+    /// Sets whether `+key` is an option ([`Prefix::Plus`]) rather than a positional argument
+    /// (`false` by default).
+    ///
+    /// Plus options are never split into clusters and take a value only with
+    /// [`ParseConfig::split_eq`] (`+key=value`), as they usually turn something off.
+    ///
+    /// ```
+    /// use vvvv::{ParseConfig, Prefix, Token};
+    ///
+    /// let config = ParseConfig::new().plus_options(true);
+    /// let args = ["-x", "+x"];
+    ///
+    /// assert_eq!(
+    ///     Token::parse_with(config, args.iter().copied()).collect::<Vec<_>>(),
+    ///     [
+    ///         Token::Short { key: 'x', value: None },
+    ///         Token::Keyed { prefix: Prefix::Plus, key: "x", value: None },
+    ///     ],
+    /// );
+    /// ```
+    pub const fn plus_options(mut self, plus: bool) -> Self {
+        self.plus = plus;
+        self
+    }
+
+    /// Sets whether `-key` is a single option ([`Prefix::SingleDash`]) rather than a cluster of
+    /// short options (`false` by default).
+    ///
+    /// Single dash options take values like long options, `--key` is still a long option.
+    ///
+    /// ```
+    /// use vvvv::{ParseConfig, Prefix, Token};
+    ///
+    /// let config = ParseConfig::new().single_dash_long(true);
+    /// let args = ["-name", "*.rs", "--x"];
+    ///
+    /// assert_eq!(
+    ///     Token::parse_with(config, args.iter().copied()).collect::<Vec<_>>(),
+    ///     [
+    ///         Token::Keyed { prefix: Prefix::SingleDash, key: "name", value: Some("*.rs") },
+    ///         Token::Long { key: "x", value: None },
+    ///     ],
+    /// );
+    /// ```
+    pub const fn single_dash_long(mut self, single_dash_long: bool) -> Self {
+        self.single_dash_long = single_dash_long;
+        self
+    }
+
+    /// Sets whether DOS style `/key` and `/key:value` are options ([`Prefix::Slash`]) rather than
+    /// positional arguments (`false` by default).
+    ///
+    /// Arguments with another `/` (i.e. `/usr/bin`) are still positional. With
+    /// [`ValuePolicy::Greedy`] an option takes the next argument as a value even if it looks like
+    /// a `/key`, so `--out /tmp` works.
+    ///
+    /// ```
+    /// use vvvv::{ParseConfig, Prefix, Token};
+    ///
+    /// let config = ParseConfig::new().slash_options(true);
+    /// let args = ["/exclude:list.txt", "/usr/bin", "--out", "/tmp"];
+    ///
+    /// assert_eq!(
+    ///     Token::parse_with(config, args.iter().copied()).collect::<Vec<_>>(),
+    ///     [
+    ///         Token::Keyed { prefix: Prefix::Slash, key: "exclude", value: Some("list.txt") },
+    ///         Token::Positional("/usr/bin"),
+    ///         Token::Long { key: "out", value: Some("/tmp") },
+    ///     ],
+    /// );
+    /// ```
+    pub const fn slash_options(mut self, slash: bool) -> Self {
+        self.slash = slash;
+        self
+    }
+
+    /// Sets whether words like `key=value` are options ([`Prefix::Bare`]) rather than positional
+    /// arguments (`false` by default).
+    ///
+    /// ```
+    /// use vvvv::{ParseConfig, Prefix, Token};
+    ///
+    /// let config = ParseConfig::new().key_value_words(true);
+    /// let args = ["if=in.img", "of=", "=x"];
+    ///
+    /// assert_eq!(
+    ///     Token::parse_with(config, args.iter().copied()).collect::<Vec<_>>(),
+    ///     [
+    ///         Token::Keyed { prefix: Prefix::Bare, key: "if", value: Some("in.img") },
+    ///         Token::Keyed { prefix: Prefix::Bare, key: "of", value: Some("") },
+    ///         Token::Positional("=x"),
+    ///     ],
+    /// );
+    /// ```
+    pub const fn key_value_words(mut self, key_value_words: bool) -> Self {
+        self.key_value_words = key_value_words;
+        self
+    }
+
+    /// Sets words which are options ([`Prefix::Keyword`]) taking the next argument as their value
+    /// (none by default).
+    ///
+    /// The next argument is taken whatever it looks like, except `--` unless it's
+    /// [positional](DashDashPolicy::Positional). The value is `None` if there are no more
+    /// arguments or `--` follows.
+    ///
+    /// ```
+    /// use vvvv::{ParseConfig, Prefix, Token};
+    ///
+    /// let config = ParseConfig::new().keywords(&["dev", "mtu"]);
+    /// let args = ["set", "dev", "eth0", "up", "mtu", "--", "dev"];
+    ///
+    /// assert_eq!(
+    ///     Token::parse_with(config, args.iter().copied()).collect::<Vec<_>>(),
+    ///     [
+    ///         Token::Positional("set"),
+    ///         Token::Keyed { prefix: Prefix::Keyword, key: "dev", value: Some("eth0") },
+    ///         Token::Positional("up"),
+    ///         Token::Keyed { prefix: Prefix::Keyword, key: "mtu", value: None },
+    ///         Token::DashDash,
+    ///         Token::Positional("dev"),
+    ///     ],
+    /// );
+    /// ```
+    pub const fn keywords(mut self, keywords: &'static [&'static str]) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    /// Returns the enabled alternative prefix of `arg` and the rest of it.
+    ///
+    /// [Bare](Prefix::Bare) and [keyword](Prefix::Keyword) options have no prefix, they are
+    /// returned whole.
+    fn prefix<'s>(&self, arg: &'s str) -> Option<(Prefix, &'s str)> {
+        let (prefix, rest) = match arg.as_bytes().first()? {
+            b'+' if self.plus => (Prefix::Plus, &arg[1..]),
+            b'-' if self.single_dash_long && !arg.starts_with("--") => {
+                (Prefix::SingleDash, &arg[1..])
+            }
+            b'/' if self.slash && !arg[1..].contains('/') => (Prefix::Slash, &arg[1..]),
+            b'-' => return None,
+            _ if self.keywords.contains(&arg) => (Prefix::Keyword, arg),
+            _ if self.key_value_words
+                && matches!(arg.split_once('='), Some((key, _)) if !key.is_empty()) =>
+            {
+                (Prefix::Bare, arg)
+            }
+            _ => return None,
+        };
+
+        match rest {
+            "" => None,
+            rest => Some((prefix, rest)),
+        }
+    }
+}
+
+impl ParseConfig {
+    /// Sets whether unambiguous prefixes of long options are accepted, i.e. `--verb` for
+    /// `--verbose` (`false` by default).
+    ///
+    /// Applies to long options and, with [single dash long
+    /// options](ParseConfig::single_dash_long), to `-name` unless it's exactly the name of an
+    /// option (i.e. a short one).
+    ///
+    /// The lexer keeps options as written, [`from_args`](crate::from_args()) and friends
+    /// [resolve](crate::help::Description::resolve_long) them against
+    /// [`FromArgs::DESCRIPTION`](crate::FromArgs::DESCRIPTION) before passing them to
+    /// [`PollInit`](crate::PollInit). An ambiguous prefix is reported as
+    /// [`Error::AmbiguousOption`](crate::Error::AmbiguousOption).
+    pub const fn abbreviations(mut self, abbreviations: bool) -> Self {
+        self.abbreviations = abbreviations;
+        self
+    }
+
+    /// Returns `true` if [abbreviations](ParseConfig::abbreviations) are accepted.
+    pub(crate) fn accepts_abbreviations(&self) -> bool {
+        self.abbreviations
+    }
+}
+
+impl Default for ParseConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// How options take values, see [`ParseConfig::values`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValuePolicy {
+    /// An option takes the next argument as its value, unless the argument starts with `-`
+    /// (`-` alone is still a value).
+    Greedy,
+    /// Values are only taken from the same argument, i.e. `--key=value` (see
+    /// [`ParseConfig::split_eq`]) or `-kvalue` (see [`ParseConfig::split_clusters`]).
+    Attached,
+}
+
+/// How `--` is handled, see [`ParseConfig::dash_dash`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DashDashPolicy {
+    /// `--` is [`Token::DashDash`], all following arguments are positional.
+    EndOfOptions,
+    /// `--` is an ordinary positional argument.
+    Positional,
+}
+
+/// How arguments which look like negative numbers are handled, see
+/// [`ParseConfig::negative_numbers`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NegativeNumbers {
+    /// `-1` is a short option `1`.
+    Options,
+    /// `-1` is a positional argument or a value of the preceding option.
+    Values,
+    /// Same as [`NegativeNumbers::Values`], unless a digit is a short option.
+    ///
+    /// This is the default of [`FromArgs::PARSE_CONFIG`](crate::FromArgs::PARSE_CONFIG),
+    /// [`from_args`](crate::from_args()) and friends [resolve](ParseConfig::resolve) it against
+    /// [`FromArgs::DESCRIPTION`](crate::FromArgs::DESCRIPTION). The lexer alone doesn't know the
+    /// options, so it treats unresolved `Auto` as `Values`.
+    Auto,
+}
+
 impl Display for Token<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -68,6 +761,15 @@ impl Display for Token<'_> {
                 Ok(())
             }
             Token::DashDash => "--".fmt(f),
+            Token::Keyed { prefix, key, value } => {
+                prefix.fmt(f).and_then(|()| key.fmt(f))?;
+                match (prefix, value) {
+                    (_, None) => Ok(()),
+                    (Prefix::Slash, Some(value)) => ':'.fmt(f).and_then(|()| value.fmt(f)),
+                    (Prefix::Bare, Some(value)) => '='.fmt(f).and_then(|()| value.fmt(f)),
+                    (_, Some(value)) => ' '.fmt(f).and_then(|()| value.fmt(f)),
+                }
+            }
         }
     }
 }
@@ -75,7 +777,9 @@ impl Display for Token<'_> {
 #[derive(Debug)]
 pub struct Parse<'a, I: Iterator<Item = &'a str>> {
     args: Peekable<I>,
-    shorts: Peekable<Chars<'a>>,
+    config: ParseConfig,
+    /// Not yet parsed rest of a short option cluster.
+    shorts: &'a str,
     pos_only: bool,
 }
 
@@ -83,15 +787,13 @@ impl<'a, I: Iterator<Item = &'a str>> Iterator for Parse<'a, I> {
     type Item = Token<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(short) = self.shorts.next() {
-            return Some(Token::Short {
-                key: short,
-                // We do not parse values after many shorts, i.e.
-                // "-vv x" => [Short('v'), Short('v'), Positional("x")]
-                // while
-                // "-v -v x" => [Short('v'), Short('v', value: "x")]
-                value: None,
-            });
+        if let Some(key) = self.shorts.chars().next() {
+            let rest = &self.shorts[key.len_utf8()..];
+            // We do not parse values after many shorts, i.e.
+            // "-vv x" => [Short('v'), Short('v'), Positional("x")]
+            // while
+            // "-v -v x" => [Short('v'), Short('v', value: "x")]
+            return Some(self.short(key, rest, false));
         }
 
         let item = self.args.next()?;
@@ -99,32 +801,45 @@ impl<'a, I: Iterator<Item = &'a str>> Iterator for Parse<'a, I> {
             return Some(Token::Positional(item));
         }
 
-        let token = match item {
-            "--" => {
-                self.pos_only = true;
-                Token::DashDash
+        let token = match (item, self.config.prefix(item)) {
+            ("--", _) => match self.config.dash_dash {
+                DashDashPolicy::EndOfOptions => {
+                    self.pos_only = true;
+                    Token::DashDash
+                }
+                DashDashPolicy::Positional => Token::Positional(item),
+            },
+            (num, _)
+                if self.config.negative_numbers != NegativeNumbers::Options
+                    && is_negative_number(num) =>
+            {
+                Token::Positional(num)
             }
-            key if key.starts_with("--") => Token::Long {
-                key: &key[2..],
-                value: next_value(&mut self.args),
+            (_, Some((prefix, rest))) => self.keyed(prefix, rest),
+            (key, None) if key.starts_with("--") => match key[2..].split_once('=') {
+                Some((key, value)) if self.config.split_eq => Token::Long {
+                    key,
+                    value: Some(value),
+                },
+                _ => Token::Long {
+                    key: &key[2..],
+                    value: self.value(),
+                },
             },
-            keys if keys.starts_with('-') => {
-                let mut chars = keys[1..].chars().peekable();
-                match (chars.next(), chars.peek()) {
-                    (None, _) => Token::Positional("-"),
-                    (Some(key), None) => Token::Short {
-                        key,
-                        value: next_value(&mut self.args),
-                    },
-                    (Some(key), Some(_)) => {
-                        self.shorts = chars;
-                        Token::Short { key, value: None }
-                    }
+            (keys, None) if keys.starts_with('-') => {
+                let keys = &keys[1..];
+                match keys.chars().next() {
+                    None => Token::Positional("-"),
+                    Some(key) => self.short(key, &keys[key.len_utf8()..], true),
                 }
             }
-            word => Token::Positional(word),
+            (word, None) => Token::Positional(word),
         };
 
+        if self.config.posix && matches!(token, Token::Positional(_)) {
+            self.pos_only = true;
+        }
+
         Some(token)
     }
 
@@ -134,7 +849,80 @@ impl<'a, I: Iterator<Item = &'a str>> Iterator for Parse<'a, I> {
     }
 }
 
+impl<'a, I: Iterator<Item = &'a str>> Parse<'a, I> {
+    /// Parses all following arguments as positionals, like after `--`.
+    ///
+    /// The rest of the current cluster is dropped, i.e. `-c` in `-bc` after `-b`.
+    ///
+    /// See also [`PollInit::stop_options`](crate::PollInit::stop_options).
+    pub fn stop_options(&mut self) {
+        self.pos_only = true;
+        self.shorts = "";
+    }
+
+    /// Returns the rules this iterator follows.
+    pub(crate) fn config(&self) -> &ParseConfig {
+        &self.config
+    }
+
+    /// Parses the short option `key` followed by the `rest` of its argument.
+    ///
+    /// `first` is `true` if `key` is the first option of the argument, only such options take
+    /// values from the next argument.
+    fn short(&mut self, key: char, rest: &'a str, first: bool) -> Token<'a> {
+        self.shorts = "";
+
+        let value = match rest.strip_prefix('=') {
+            Some(value) if self.config.split_eq => Some(value),
+            _ if rest.is_empty() && first => self.value(),
+            _ if rest.is_empty() => None,
+            _ if !self.config.split_clusters => Some(rest),
+            _ => {
+                self.shorts = rest;
+                None
+            }
+        };
+
+        Token::Short { key, value }
+    }
+
+    /// Parses the option with an alternative `prefix`, `rest` is the argument without it.
+    fn keyed(&mut self, prefix: Prefix, rest: &'a str) -> Token<'a> {
+        let (key, value) = match prefix {
+            Prefix::Slash => match rest.split_once(':') {
+                Some((key, value)) => (key, Some(value)),
+                None => (rest, None),
+            },
+            Prefix::Keyword => {
+                let dash_dash = self.config.dash_dash;
+                let value = self
+                    .args
+                    .next_if(|&arg| arg != "--" || dash_dash == DashDashPolicy::Positional);
+                (rest, value)
+            }
+            Prefix::Plus | Prefix::SingleDash | Prefix::Bare => match rest.split_once('=') {
+                Some((key, value)) if self.config.split_eq || prefix == Prefix::Bare => {
+                    (key, Some(value))
+                }
+                _ if prefix == Prefix::SingleDash => (rest, self.value()),
+                _ => (rest, None),
+            },
+        };
+
+        Token::Keyed { prefix, key, value }
+    }
+
+    /// Takes the next argument as a value, if the config allows.
+    fn value(&mut self) -> Option<&'a str> {
+        match self.config.values {
+            ValuePolicy::Greedy => next_value(&mut self.args, &self.config),
+            ValuePolicy::Attached => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum OwnToken {
     Positional(Box<str>),
     Short {
@@ -146,11 +934,16 @@ pub enum OwnToken {
         value: Option<Box<str>>,
     },
     DashDash,
+    Keyed {
+        prefix: Prefix,
+        key: Box<str>,
+        value: Option<Box<str>>,
+    },
 }
 
 impl OwnToken {
     /// Borrow owned token as borrowed token.
-    pub fn borrow(&self) -> Token {
+    pub fn borrow(&self) -> Token<'_> {
         match self {
             OwnToken::Positional(s) => Token::Positional(s.as_ref()),
             OwnToken::Short { key, value } => Token::Short {
@@ -162,10 +955,21 @@ impl OwnToken {
                 value: value.as_ref().map(<_>::as_ref),
             },
             OwnToken::DashDash => Token::DashDash,
+            OwnToken::Keyed { prefix, key, value } => Token::Keyed {
+                prefix: *prefix,
+                key: key.as_ref(),
+                value: value.as_ref().map(<_>::as_ref),
+            },
         }
     }
 }
 
+impl<'a> From<&'a OwnToken> for Token<'a> {
+    fn from(token: &'a OwnToken) -> Self {
+        token.borrow()
+    }
+}
+
 impl Display for OwnToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.borrow().fmt(f)
@@ -187,18 +991,46 @@ impl IntoOwned for Token<'_> {
                 value: value.map(Into::into),
             },
             Token::DashDash => OwnToken::DashDash,
+            Token::Keyed { prefix, key, value } => OwnToken::Keyed {
+                prefix,
+                key: key.into(),
+                value: value.map(Into::into),
+            },
         }
     }
 }
 
 // If next value in iterator doesn't start with ('-' + any char) returns Some(next), otherwise returns None
-fn next_value<'a>(args: &mut Peekable<impl Iterator<Item = &'a str>>) -> Option<&'a str> {
+fn next_value<'a>(
+    args: &mut Peekable<impl Iterator<Item = &'a str>>,
+    config: &ParseConfig,
+) -> Option<&'a str> {
     match args.peek() {
-        Some(&x) if x == "-" || !x.starts_with('-') => args.next(),
+        Some(&"-") => args.next(),
+        Some(&x)
+            if config.negative_numbers != NegativeNumbers::Options && is_negative_number(x) =>
+        {
+            args.next()
+        }
+        // `+x` options are not values either, but `/x` and `key=value` are: `--out /tmp` and
+        // `--define key=value` are much more likely than an option without a value followed by
+        // another option
+        Some(&x) if matches!(config.prefix(x), Some((Prefix::Plus, _))) => None,
+        Some(&x) if !x.starts_with('-') => args.next(),
         _ => None,
     }
 }
 
+/// Returns `true` if `s` is a negative decimal number, i.e. `-1`, `-0.5` or `-1e3`.
+fn is_negative_number(s: &str) -> bool {
+    match s.strip_prefix('-') {
+        Some(num) => {
+            num.starts_with(|c: char| c.is_ascii_digit() || c == '.') && num.parse::<f64>().is_ok()
+        }
+        None => false,
+    }
+}
+
 #[test]
 fn ast_parse() {
     let args = [
@@ -282,3 +1114,544 @@ fn ast_parse() {
         &expected
     );
 }
+
+#[test]
+fn parse_config() {
+    use Token::{DashDash, Positional};
+
+    fn short(key: char, value: Option<&str>) -> Token<'_> {
+        Token::Short { key, value }
+    }
+
+    fn long<'a>(key: &'a str, value: Option<&'a str>) -> Token<'a> {
+        Token::Long { key, value }
+    }
+
+    fn parse<'a>(config: ParseConfig, args: &[&'a str]) -> Vec<Token<'a>> {
+        Token::parse_with(config, args.iter().copied()).collect()
+    }
+
+    // Every case depends on a single rule: args, the rule, tokens if the rule is on and tokens
+    // if it's off. The cases are checked with all combinations of rules.
+    type Rule = fn(&ParseConfig) -> bool;
+    type Case<'t> = (&'t [&'t str], Rule, &'t [Token<'t>], &'t [Token<'t>]);
+    let cases: &[Case<'_>] = &[
+        (
+            &["--key", "value"],
+            |c| c.values == ValuePolicy::Greedy,
+            &[long("key", Some("value"))],
+            &[long("key", None), Positional("value")],
+        ),
+        (
+            &["-xyz"],
+            |c| c.split_clusters,
+            &[short('x', None), short('y', None), short('z', None)],
+            &[short('x', Some("yz"))],
+        ),
+        (
+            &["--"],
+            |c| c.dash_dash == DashDashPolicy::EndOfOptions,
+            &[DashDash],
+            &[Positional("--")],
+        ),
+        (
+            &["--key=value"],
+            |c| c.split_eq,
+            &[long("key", Some("value"))],
+            &[long("key=value", None)],
+        ),
+        (
+            &["-5"],
+            |c| c.negative_numbers == NegativeNumbers::Values,
+            &[Positional("-5")],
+            &[short('5', None)],
+        ),
+        (
+            &["file", "-x"],
+            |c| c.posix,
+            &[Positional("file"), Positional("-x")],
+            &[Positional("file"), short('x', None)],
+        ),
+    ];
+
+    for bits in 0..64 {
+        let on = |bit: u32| bits & (1 << bit) != 0;
+        let config = ParseConfig::new()
+            .values(if on(0) {
+                ValuePolicy::Greedy
+            } else {
+                ValuePolicy::Attached
+            })
+            .split_clusters(on(1))
+            .dash_dash(if on(2) {
+                DashDashPolicy::EndOfOptions
+            } else {
+                DashDashPolicy::Positional
+            })
+            .split_eq(on(3))
+            .negative_numbers(if on(4) {
+                NegativeNumbers::Values
+            } else {
+                NegativeNumbers::Options
+            })
+            .posix(on(5));
+
+        for (args, rule, if_on, if_off) in cases {
+            let expected = if rule(&config) { if_on } else { if_off };
+            assert_eq!(parse(config, args), *expected, "{:?} {:?}", config, args);
+        }
+    }
+
+    // Rules which interact with each other
+    let default = ParseConfig::DEFAULT;
+    let cases: &[(ParseConfig, &[&str], &[Token<'_>])] = &[
+        (
+            default.split_eq(true),
+            &["-ab=1"],
+            &[short('a', None), short('b', Some("1"))],
+        ),
+        (
+            default,
+            &["-ab=1"],
+            &[
+                short('a', None),
+                short('b', None),
+                short('=', None),
+                short('1', None),
+            ],
+        ),
+        (
+            default.split_clusters(false).split_eq(true),
+            &["-k=v"],
+            &[short('k', Some("v"))],
+        ),
+        (
+            default.split_clusters(false),
+            &["-k=v"],
+            &[short('k', Some("=v"))],
+        ),
+        (
+            default.negative_numbers(NegativeNumbers::Values),
+            &["--offset", "-5", "-1.5e3", "-inf"],
+            &[
+                long("offset", Some("-5")),
+                Positional("-1.5e3"),
+                short('i', None),
+                short('n', None),
+                short('f', None),
+            ],
+        ),
+        (
+            default,
+            &["--offset", "-5"],
+            &[long("offset", None), short('5', None)],
+        ),
+        (
+            default
+                .negative_numbers(NegativeNumbers::Values)
+                .values(ValuePolicy::Attached),
+            &["--offset", "-5"],
+            &[long("offset", None), Positional("-5")],
+        ),
+        (
+            default.split_clusters(false).values(ValuePolicy::Attached),
+            &["-ofile", "-o", "file", "-"],
+            &[
+                short('o', Some("file")),
+                short('o', None),
+                Positional("file"),
+                Positional("-"),
+            ],
+        ),
+        (
+            default.dash_dash(DashDashPolicy::Positional),
+            &["-x", "--", "--", "-y"],
+            &[
+                short('x', None),
+                Positional("--"),
+                Positional("--"),
+                short('y', None),
+            ],
+        ),
+        (
+            default
+                .posix(true)
+                .negative_numbers(NegativeNumbers::Values),
+            &["-x", "-5", "-y"],
+            &[short('x', Some("-5")), short('y', None)],
+        ),
+        (
+            default.posix(true),
+            &["-", "-y"],
+            &[Positional("-"), Positional("-y")],
+        ),
+    ];
+
+    for (config, args, expected) in cases {
+        assert_eq!(parse(*config, args), *expected, "{:?} {:?}", config, args);
+    }
+}
+
+#[test]
+fn stop_options() {
+    let args = ["-abc", "-d", "--", "e"];
+    let mut parse = Token::parse(args.iter().copied());
+    assert_eq!(
+        parse.next(),
+        Some(Token::Short {
+            key: 'a',
+            value: None,
+        })
+    );
+
+    // the rest of `-abc` is dropped
+    parse.stop_options();
+    assert_eq!(
+        parse.collect::<Vec<_>>(),
+        [
+            Token::Positional("-d"),
+            Token::Positional("--"),
+            Token::Positional("e"),
+        ]
+    );
+}
+
+#[test]
+fn posixly_correct() {
+    // other tests don't opt in, so they are not affected by the variable
+    env::set_var("POSIXLY_CORRECT", "1");
+    let config = ParseConfig::new();
+    assert_eq!(config.resolve(&Description::None), config);
+    assert_eq!(
+        config.posixly_correct(true).resolve(&Description::None),
+        config.posixly_correct(true).posix(true)
+    );
+
+    env::remove_var("POSIXLY_CORRECT");
+    assert_eq!(
+        config.posixly_correct(true).resolve(&Description::None),
+        config.posixly_correct(true)
+    );
+}
+
+#[test]
+fn negative_numbers() {
+    // `Token::parse` keeps negative numbers as short options
+    assert_eq!(
+        Token::parse(["-5"].iter().copied()).collect::<Vec<_>>(),
+        [Token::Short {
+            key: '5',
+            value: None,
+        }]
+    );
+
+    let values = ParseConfig::DEFAULT.negative_numbers(NegativeNumbers::Values);
+    let numbers = [
+        "-3", "-10", "-0", "-1.5", "-.5", "-2.", "-1e3", "-1E-3", "-2.5e+10",
+    ];
+    for num in numbers.iter().copied() {
+        let args = ["--delta", num, num];
+        assert_eq!(
+            Token::parse_with(values, args.iter().copied()).collect::<Vec<_>>(),
+            [
+                Token::Long {
+                    key: "delta",
+                    value: Some(num),
+                },
+                Token::Positional(num),
+            ],
+            "{}",
+            num
+        );
+    }
+
+    let not_numbers = ["-e3", "-inf", "-nan", "-1x", "-1e", "-."];
+    for arg in not_numbers.iter().copied() {
+        let tokens: Vec<_> = Token::parse_with(values, Some(arg).into_iter()).collect();
+        assert!(
+            tokens.iter().all(|t| matches!(t, Token::Short { .. })),
+            "{}",
+            arg
+        );
+    }
+
+    let descr = Description::DEFAULT
+        .name("seek")
+        .options(&[crate::help::Opt {
+            aliases: &[crate::help::Alias {
+                name: Name::Short('1'),
+                visible: false,
+            }],
+            ..crate::help::Opt::DEFAULT
+        }]);
+    let auto = ParseConfig::DEFAULT.negative_numbers(NegativeNumbers::Auto);
+    let config = auto.resolve(&descr);
+    assert_eq!(
+        Token::parse_with(config, ["-10"].iter().copied()).collect::<Vec<_>>(),
+        [
+            Token::Short {
+                key: '1',
+                value: None,
+            },
+            Token::Short {
+                key: '0',
+                value: None,
+            },
+        ]
+    );
+    assert_eq!(auto.resolve(&Description::Raw("")), values);
+}
+
+#[test]
+fn unparse() {
+    let config = ParseConfig::new();
+    let cases: &[(ParseConfig, &[&str], &[&str])] = &[
+        (config, &["-vx", "file"], &["-vx", "file"]),
+        (config, &["-v", "-x", "-o", "out"], &["-vx", "-o", "out"]),
+        (
+            config.negative_numbers(NegativeNumbers::Values),
+            &["-v", "-5", "--", "-x"],
+            &["-v", "-5", "--", "-x"],
+        ),
+        (
+            config.split_eq(true),
+            &["--out=-x", "-k", "-", "--in=file", "-e=", "-q==x"],
+            &["--out=-x", "-k", "-", "--in", "file", "-e", "", "-q==x"],
+        ),
+        (
+            config.split_clusters(false),
+            &["-o", "a", "-k", ""],
+            &["-oa", "-k", ""],
+        ),
+        (
+            config.values(ValuePolicy::Attached),
+            &["-v", "x"],
+            &["-v", "x"],
+        ),
+    ];
+
+    for &(config, args, expected) in cases {
+        let tokens: Vec<_> = Token::parse_with(config, args.iter().copied()).collect();
+        assert_eq!(
+            Token::unparse_with(config, tokens.iter().copied()).unwrap(),
+            expected,
+            "{:?}",
+            args
+        );
+    }
+
+    let err = |tokens: &[Token<'_>]| Token::unparse(tokens.iter().copied()).unwrap_err().index;
+    let short = |key, value| Token::Short { key, value };
+    // `-v x` is `-v` with the value `x`
+    assert_eq!(err(&[short('v', None), Token::Positional("x")]), 0);
+    assert_eq!(err(&[short('v', Some("-x"))]), 0);
+    // positionals which look like options are written after `--`, unless an option follows
+    assert_eq!(
+        Token::unparse(vec![
+            short('v', None),
+            Token::Positional("-x"),
+            Token::Positional("y")
+        ])
+        .unwrap(),
+        ["-v", "--", "-x", "y"]
+    );
+    assert_eq!(err(&[Token::Positional("-x"), short('v', None)]), 0);
+    // `--` doesn't help if it's a positional too
+    let positional = ParseConfig::DEFAULT.dash_dash(DashDashPolicy::Positional);
+    let tokens = [Token::Positional("-x")];
+    assert_eq!(
+        Token::unparse_with(positional, tokens.iter().copied())
+            .unwrap_err()
+            .index,
+        0
+    );
+    assert_eq!(err(&[Token::DashDash, short('v', None)]), 1);
+    assert_eq!(Token::unparse(vec![short('5', None)]).unwrap(), ["-5"]);
+
+    // `-5` is a value if negative numbers are values, but `-5v` is not a number
+    let values = ParseConfig::DEFAULT.negative_numbers(NegativeNumbers::Values);
+    let tokens = [short('5', None), short('v', None)];
+    assert_eq!(
+        Token::unparse_with(values, tokens[..1].iter().copied())
+            .unwrap_err()
+            .index,
+        0
+    );
+    assert_eq!(
+        Token::unparse_with(values, tokens.iter().copied()).unwrap(),
+        ["-5v"]
+    );
+}
+
+#[cfg(test)]
+mod unparse_props {
+    use proptest::prelude::*;
+
+    use super::{DashDashPolicy, NegativeNumbers, OwnToken, ParseConfig, Token, ValuePolicy};
+
+    fn config() -> impl Strategy<Value = ParseConfig> {
+        (any::<[bool; 10]>(), 0..3usize).prop_map(|(bits, negative)| {
+            ParseConfig::new()
+                .values(if bits[0] {
+                    ValuePolicy::Greedy
+                } else {
+                    ValuePolicy::Attached
+                })
+                .split_clusters(bits[1])
+                .dash_dash(if bits[2] {
+                    DashDashPolicy::EndOfOptions
+                } else {
+                    DashDashPolicy::Positional
+                })
+                .split_eq(bits[3])
+                .posix(bits[4])
+                .plus_options(bits[5])
+                .single_dash_long(bits[6])
+                .slash_options(bits[7])
+                .key_value_words(bits[8])
+                .keywords(if bits[9] { &["ab"] } else { &[] })
+                .negative_numbers(
+                    [
+                        NegativeNumbers::Options,
+                        NegativeNumbers::Values,
+                        NegativeNumbers::Auto,
+                    ][negative],
+                )
+        })
+    }
+
+    fn token() -> impl Strategy<Value = OwnToken> {
+        let value = proptest::option::of("[a-z =-]{0,3}".prop_map(Into::into));
+        prop_oneof![
+            "([a-z =]{0,3}|-)".prop_map(|s| OwnToken::Positional(s.into())),
+            ("[a-z5]", value.clone()).prop_map(|(key, value)| OwnToken::Short {
+                key: key.chars().next().unwrap(),
+                value,
+            }),
+            ("[a-z]{1,3}", value).prop_map(|(key, value)| OwnToken::Long {
+                key: key.into(),
+                value,
+            }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn lexed_roundtrip(
+            config in config(),
+            args in prop::collection::vec("[-+/]{0,3}[a-c5.=:/]{0,3}", 0..6),
+        ) {
+            let tokens: Vec<_> = Token::parse_with(config, args.iter().map(String::as_str)).collect();
+            let unparsed = Token::unparse_with(config, tokens.iter().copied()).unwrap();
+            let relexed: Vec<_> =
+                Token::parse_with(config, unparsed.iter().map(String::as_str)).collect();
+            prop_assert_eq!(relexed, tokens);
+        }
+
+        #[test]
+        fn attached_roundtrip(tokens in prop::collection::vec(token(), 0..6)) {
+            let config = ParseConfig::new()
+                .split_eq(true)
+                .values(ValuePolicy::Attached)
+                .negative_numbers(NegativeNumbers::Options);
+            let unparsed = Token::unparse_with(config, &tokens).unwrap();
+            let relexed: Vec<_> =
+                Token::parse_with(config, unparsed.iter().map(String::as_str)).collect();
+            let tokens: Vec<_> = tokens.iter().map(OwnToken::borrow).collect();
+            prop_assert_eq!(relexed, tokens);
+        }
+    }
+}
+
+#[test]
+fn prefixes() {
+    let config = ParseConfig::new()
+        .plus_options(true)
+        .single_dash_long(true)
+        .slash_options(true)
+        .negative_numbers(NegativeNumbers::Values);
+    let keyed = |prefix, key, value| Token::Keyed { prefix, key, value };
+    let args = [
+        "-name", "*.rs", "+x", "-o", "+v", "/w:80", "/q", "/usr/bin", "-5", "--long", "-", "+",
+        "--out", "/tmp",
+    ];
+
+    assert_eq!(
+        Token::parse_with(config, args.iter().copied()).collect::<Vec<_>>(),
+        [
+            keyed(Prefix::SingleDash, "name", Some("*.rs")),
+            keyed(Prefix::Plus, "x", None),
+            keyed(Prefix::SingleDash, "o", None),
+            keyed(Prefix::Plus, "v", None),
+            keyed(Prefix::Slash, "w", Some("80")),
+            keyed(Prefix::Slash, "q", None),
+            Token::Positional("/usr/bin"),
+            Token::Positional("-5"),
+            Token::Long {
+                key: "long",
+                value: Some("-")
+            },
+            Token::Positional("+"),
+            Token::Long {
+                key: "out",
+                value: Some("/tmp")
+            },
+        ]
+    );
+
+    let config = config.split_eq(true);
+    let args = ["+x=1", "-name=a", "/k=v"];
+    assert_eq!(
+        Token::parse_with(config, args.iter().copied()).collect::<Vec<_>>(),
+        [
+            keyed(Prefix::Plus, "x", Some("1")),
+            keyed(Prefix::SingleDash, "name", Some("a")),
+            keyed(Prefix::Slash, "k=v", None),
+        ]
+    );
+
+    let tokens = [
+        keyed(Prefix::Plus, "x", Some("1")),
+        keyed(Prefix::SingleDash, "name", Some("-a")),
+        keyed(Prefix::Slash, "w", Some("")),
+    ];
+    let args = Token::unparse_with(config, tokens.iter().copied()).unwrap();
+    assert_eq!(args, ["+x=1", "-name=-a", "/w:"]);
+    assert_eq!(tokens[2].to_string(), "/w:");
+}
+
+#[test]
+fn bare() {
+    let config = ParseConfig::new().key_value_words(true).keywords(&["dev"]);
+    let keyed = |prefix, key, value| Token::Keyed { prefix, key, value };
+    let bare = |key, value| keyed(Prefix::Bare, key, value);
+    let args = [
+        "bs=4k", "if=a", "dev", "-x", "x=", "=y", "word", "--define", "a=b", "dev", "--", "dev",
+    ];
+    let tokens: Vec<_> = Token::parse_with(config, args.iter().copied()).collect();
+
+    assert_eq!(
+        tokens,
+        [
+            bare("bs", Some("4k")),
+            bare("if", Some("a")),
+            keyed(Prefix::Keyword, "dev", Some("-x")),
+            bare("x", Some("")),
+            Token::Positional("=y"),
+            Token::Positional("word"),
+            Token::Long {
+                key: "define",
+                value: Some("a=b")
+            },
+            keyed(Prefix::Keyword, "dev", None),
+            Token::DashDash,
+            Token::Positional("dev"),
+        ]
+    );
+    assert_eq!(tokens[2].to_string(), "dev -x");
+    assert_eq!(tokens[3].to_string(), "x=");
+    assert_eq!(
+        Token::unparse_with(config, tokens.iter().copied()).unwrap(),
+        args
+    );
+}
@@ -0,0 +1,362 @@
+//! Streaming word wrapping.
+//!
+//! `textwrap` requires the whole string up-front, but help is rendered from several chunks (i.e.
+//! description followed by ` [default: `, default value and `]`), so this module implements a
+//! wrapper which accepts text chunk by chunk. Only the current line is buffered.
+//!
+//! Lines are broken at Unicode line break opportunities (UAX #14), explicit `\n` always starts a
+//! new line (so `\n\n` separates paragraphs). Words which don't fit into a line are broken at the
+//! character boundary. ANSI escape sequences have zero width and are never broken.
+
+use std::fmt;
+
+use unicode_linebreak::linebreaks;
+use unicode_width::UnicodeWidthChar;
+
+/// Word wrapper which writes wrapped text to `out`.
+///
+/// Every line except the first one is indented with `indent` spaces.
+pub(crate) struct Wrap<'o> {
+    out: &'o mut dyn fmt::Write,
+    limit: usize,
+    indent: usize,
+    /// Additional indentation of the following lines, see [`Wrap::hang`].
+    hang: usize,
+    /// Column at which `buf` starts.
+    col: usize,
+    /// Number of spaces which need to be written before the rest of the current line.
+    ///
+    /// Indentation is written lazily, so empty lines don't have trailing whitespace.
+    pending: usize,
+    /// Not yet written part of the current line.
+    buf: String,
+}
+
+impl<'o> Wrap<'o> {
+    /// Creates a wrapper which keeps lines at most `limit` columns wide.
+    ///
+    /// `start` is the column at which the first line starts (i.e. it's already written).
+    pub(crate) fn new(
+        out: &'o mut dyn fmt::Write,
+        limit: usize,
+        start: usize,
+        indent: usize,
+    ) -> Self {
+        Self {
+            out,
+            limit,
+            indent,
+            hang: 0,
+            col: start,
+            pending: 0,
+            buf: String::new(),
+        }
+    }
+
+    /// Pads the first line with `spaces` spaces, they are only written if the line is not empty.
+    pub(crate) fn pad(mut self, spaces: usize) -> Self {
+        self.pending += spaces;
+        self.col += spaces;
+        self
+    }
+
+    /// Indents lines wrapped from now on by additional `spaces` until the next explicit `\n`, i.e.
+    /// to hang the text of a bullet under its first word.
+    pub(crate) fn hang(&mut self, spaces: usize) {
+        self.hang = spaces;
+    }
+
+    /// Wraps `text` which continues text pushed earlier.
+    pub(crate) fn push(&mut self, text: &str) -> fmt::Result {
+        let mut lines = text.split('\n');
+
+        // `split` always returns at least one item
+        self.buf.push_str(lines.next().unwrap_or(""));
+        self.wrap(false)?;
+
+        for line in lines {
+            self.wrap(true)?;
+            self.hang = 0;
+            self.newline()?;
+            self.buf.push_str(line);
+            self.wrap(false)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the rest of the text.
+    ///
+    /// Doesn't write a trailing newline.
+    pub(crate) fn finish(mut self) -> fmt::Result {
+        self.wrap(true)
+    }
+
+    /// Writes complete lines from `buf`.
+    ///
+    /// Break opportunity at the end of `buf` is unknown until more text arrives (`"foo"` may be
+    /// followed by `"bar"`), so it's only used if `eol` is `true`, i.e. the line is known to end
+    /// here.
+    fn wrap(&mut self, eol: bool) -> fmt::Result {
+        loop {
+            let avail = self.limit.saturating_sub(self.col);
+
+            if width(self.buf.trim_end()) <= avail {
+                if eol {
+                    let line = self.buf.trim_end().len();
+                    self.emit(line)?;
+                    self.buf.clear();
+                }
+
+                return Ok(());
+            }
+
+            let escapes = escapes(&self.buf);
+            let brk = linebreaks(&self.buf)
+                .map(|(i, _)| i)
+                .filter(|&i| i != self.buf.len() && !self.buf[..i].trim_end().is_empty())
+                .filter(|&i| !escapes.iter().any(|&(s, e)| s < i && i < e))
+                .take_while(|&i| width(self.buf[..i].trim_end()) <= avail)
+                .last();
+
+            let (line, rest) = match brk {
+                Some(i) => (self.buf[..i].trim_end().len(), i),
+                None => {
+                    let i = hard_break(&self.buf, avail);
+                    (i, i)
+                }
+            };
+
+            if rest == self.buf.len() {
+                // A single character which is wider than the line, it may be followed by more
+                // text, so the line is not complete yet
+                if eol {
+                    self.emit(rest)?;
+                    self.buf.clear();
+                }
+
+                return Ok(());
+            }
+
+            self.emit(line)?;
+            self.newline()?;
+            self.buf.drain(..rest);
+
+            // Whitespace at the wrap point is not needed
+            let spaces = self.buf.len() - self.buf.trim_start_matches(' ').len();
+            self.buf.drain(..spaces);
+        }
+    }
+
+    /// Writes `buf[..len]` to the current line.
+    fn emit(&mut self, len: usize) -> fmt::Result {
+        if len == 0 {
+            return Ok(());
+        }
+
+        if self.pending != 0 {
+            write!(self.out, "{:1$}", "", self.pending)?;
+            self.pending = 0;
+        }
+
+        self.out.write_str(&self.buf[..len])
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        self.col = self.indent + self.hang;
+        self.pending = self.indent + self.hang;
+        self.out.write_char('\n')
+    }
+}
+
+/// Returns the length of the longest prefix of `s` with width of at most `avail`.
+///
+/// The prefix always contains at least one visible character (so wrapping always progresses)
+/// and never ends in the middle of an escape sequence or before a zero-width character.
+fn hard_break(s: &str, avail: usize) -> usize {
+    let mut escape = Escape::default();
+    let mut width = 0;
+    let mut end = 0;
+
+    for (i, ch) in s.char_indices() {
+        if escape.skip(ch) {
+            continue;
+        }
+
+        let w = ch.width().unwrap_or(0);
+        if w != 0 && end != 0 && width + w > avail {
+            return end;
+        }
+
+        width += w;
+        end = i + ch.len_utf8();
+    }
+
+    s.len()
+}
+
+/// Returns byte ranges of ANSI escape sequences in `s`.
+fn escapes(s: &str) -> Vec<(usize, usize)> {
+    let mut escape = Escape::default();
+    let mut ranges = Vec::new();
+
+    for (i, ch) in s.char_indices() {
+        let inside = escape.state != 0;
+        match (inside, escape.skip(ch)) {
+            (false, true) => ranges.push((i, s.len())),
+            (true, _) if escape.state == 0 => {
+                if let Some(last) = ranges.last_mut() {
+                    last.1 = i + ch.len_utf8();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    ranges
+}
+
+/// Returns width of `s` ignoring ANSI escape sequences (i.e. `\x1b[1m`).
+pub(crate) fn width(s: &str) -> usize {
+    let mut escape = Escape::default();
+    s.chars()
+        .filter(|&ch| !escape.skip(ch))
+        .map(|ch| ch.width().unwrap_or(0))
+        .sum()
+}
+
+/// State machine which detects ANSI CSI escape sequences.
+#[derive(Default)]
+struct Escape {
+    // 0 - not in an escape sequence, 1 - after `ESC`, 2 - after `ESC [`
+    state: u8,
+}
+
+impl Escape {
+    /// Returns `true` if `ch` is a part of an escape sequence.
+    fn skip(&mut self, ch: char) -> bool {
+        match (self.state, ch) {
+            (0, '\x1b') => self.state = 1,
+            (0, _) => return false,
+            (1, '[') => self.state = 2,
+            (1, _) => self.state = 0,
+            // final byte
+            (_, '\x40'..='\x7e') => self.state = 0,
+            (_, _) => {}
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{width, Wrap};
+
+    fn wrap(chunks: &[&str], limit: usize, start: usize, indent: usize) -> String {
+        let mut out = String::new();
+        let mut wrap = Wrap::new(&mut out, limit, start, indent);
+        for chunk in chunks {
+            wrap.push(chunk).unwrap();
+        }
+        wrap.finish().unwrap();
+        out
+    }
+
+    #[test]
+    fn words() {
+        let s = "DO NOT BECOME ADDICTED TO OXYGEN";
+        assert_eq!(wrap(&[s], 100, 0, 0), s);
+        assert_eq!(wrap(&[s], 13, 0, 0), "DO NOT BECOME\nADDICTED TO\nOXYGEN");
+        assert_eq!(
+            wrap(&[s], 12, 0, 2),
+            "DO NOT\n  BECOME\n  ADDICTED\n  TO OXYGEN"
+        );
+        assert_eq!(wrap(&[s], 12, 6, 0), "DO NOT\nBECOME\nADDICTED TO\nOXYGEN");
+        assert_eq!(wrap(&["áá ááá", " áááááá"], 8, 0, 0), "áá ááá\náááááá");
+    }
+
+    #[test]
+    fn chunks() {
+        let chunks = ["output file", " [default: ", "out.txt", "]"];
+        assert_eq!(wrap(&chunks, 20, 0, 0), "output file\n[default: out.txt]");
+        assert_eq!(wrap(&chunks, 26, 0, 0), "output file [default:\nout.txt]");
+        assert_eq!(wrap(&["DO N", "", "OT"], 4, 0, 0), "DO\nNOT");
+        assert_eq!(wrap(&[], 4, 0, 0), "");
+    }
+
+    #[test]
+    fn newlines() {
+        assert_eq!(
+            wrap(&["first paragraph\n\nsecond\nthird"], 11, 0, 2),
+            "first\n  paragraph\n\n  second\n  third"
+        );
+        assert_eq!(wrap(&["a", "\n", "b\n"], 10, 0, 4), "a\n    b\n");
+    }
+
+    #[test]
+    fn narrow() {
+        assert_eq!(wrap(&["abc de"], 2, 0, 0), "ab\nc\nde");
+        assert_eq!(wrap(&["abc de"], 0, 0, 0), "a\nb\nc\nd\ne");
+        assert_eq!(wrap(&["abc"], 4, 8, 6), "a\n      b\n      c");
+        assert_eq!(wrap(&["表表"], 1, 0, 0), "表\n表");
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(width("\x1b[1;4mUsage:\x1b[0m"), 6);
+        assert_eq!(
+            wrap(&["\x1b[1mDO NOT\x1b[0m BECOME"], 6, 0, 0),
+            "\x1b[1mDO NOT\x1b[0m\nBECOME"
+        );
+        assert_eq!(
+            wrap(&["DO \x1b[1mNOT BECOME"], 5, 0, 0),
+            "DO\n\x1b[1mNOT\nBECOM\nE"
+        );
+        assert_eq!(wrap(&["\x1b[1mab\x1b[0m"], 1, 0, 0), "\x1b[1ma\nb\x1b[0m");
+    }
+
+    proptest! {
+        #[test]
+        fn lines_fit(
+            chunks in prop::collection::vec("[a-z 表á\\-/(),.\n]{0,12}", 0..8),
+            limit in 0usize..30,
+        ) {
+            let chunks: Vec<_> = chunks.iter().map(String::as_str).collect();
+            let out = wrap(&chunks, limit, 0, 0);
+
+            for line in out.lines() {
+                prop_assert!(
+                    width(line) <= limit || line.chars().count() == 1,
+                    "{:?} is wider than {}", line, limit
+                );
+            }
+
+            let strip = |s: &str| s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+            prop_assert_eq!(strip(&out), strip(&chunks.concat()));
+        }
+
+        #[test]
+        fn indented_lines_fit(
+            text in "[a-z ]{0,60}",
+            limit in 0usize..30,
+            start in 0usize..30,
+            indent in 0usize..30,
+        ) {
+            let out = wrap(&[&text], limit, start, indent);
+
+            for (i, line) in out.lines().enumerate() {
+                let col = if i == 0 { start } else { 0 };
+                let content = if i == 0 { line } else { &line[indent.min(line.len())..] };
+                let ind = if i == 0 { start } else { indent };
+
+                prop_assert!(
+                    col + width(line) <= limit || ind >= limit || content.chars().count() == 1,
+                    "{:?} is wider than {}", line, limit
+                );
+            }
+        }
+    }
+}